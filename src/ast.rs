@@ -1,28 +1,145 @@
-use crate::scanner::token::{self, Token};
+use std::borrow::Cow;
+
+use crate::{lints::Warning, scanner::token};
+
+// source span of the statement an instruction was assembled from
+#[derive(Debug, Clone, Copy)]
+pub struct Location {
+    pub line: usize,
+    pub start: usize,
+    pub len: usize,
+}
+
+pub struct Program<'a> {
+    pub instructions: Vec<HackInstruction<'a>>,
+    pub locations: Vec<Location>,
+    pub labels: Vec<Label<'a>>,
+    pub references: Vec<SymbolRef<'a>>,
+    pub variables: Vec<Variable<'a>>,
+    // whether each instruction came from expanding a pseudo-instruction
+    pub expanded: Vec<bool>,
+    pub warnings: Vec<Warning>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Label,
+    Variable,
+    Predefined,
+    Constant,
+}
+
+impl SymbolKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SymbolKind::Label => "label",
+            SymbolKind::Variable => "variable",
+            SymbolKind::Predefined => "predefined",
+            SymbolKind::Constant => "constant",
+        }
+    }
+}
+
+// numeric labels like `1:` have no name
+#[derive(Debug)]
+pub struct Label<'a> {
+    pub name: Option<Cow<'a, str>>,
+    pub address: u16,
+    pub location: Location,
+}
+
+// variables placed with `.var` are not allocated, they keep their address
+#[derive(Debug)]
+pub struct Variable<'a> {
+    pub name: Cow<'a, str>,
+    pub address: u16,
+    pub location: Location,
+    pub allocated: bool,
+}
+
+// a use of a symbol by an instruction, data word or directive expression like `.assert`
+#[derive(Debug)]
+pub struct SymbolRef<'a> {
+    pub name: Cow<'a, str>,
+    pub kind: SymbolKind,
+    pub instruction: Option<usize>,
+    pub location: Location,
+}
 
 #[derive(Debug)]
 pub enum HackInstruction<'a> {
     A(AInstruction<'a>),
     C(CInstruction<'a>),
+    // raw word placed in ROM by a data directive
+    Word(AInstruction<'a>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AInstruction<'a> {
     Number(u16),
-    Identifier(&'a str),
+    Identifier(Cow<'a, str>),
 }
 
 #[derive(Debug)]
 pub struct CInstruction<'a> {
-    dest: Option<&'a Token<'a>>,
+    dest: Option<token::TokenKind<'a>>,
     comp: Comp,
-    jump: Option<&'a Token<'a>>,
+    jump: Option<token::TokenKind<'a>>,
 }
 
 impl<'a> CInstruction<'a> {
-    pub fn new(dest: Option<&'a Token<'a>>, comp: Comp, jump: Option<&'a Token<'a>>) -> Self {
+    pub fn new(
+        dest: Option<token::TokenKind<'a>>,
+        comp: Comp,
+        jump: Option<token::TokenKind<'a>>,
+    ) -> Self {
         Self { dest, comp, jump }
     }
+
+    pub fn reads_a(&self) -> bool {
+        self.comp.uses_a()
+    }
+
+    pub fn writes_a(&self) -> bool {
+        self.dest.is_some_and(|dest| dest.writes_a())
+    }
+
+    // reads or writes RAM[A]
+    pub fn accesses_memory(&self) -> bool {
+        self.reads_memory() || self.writes_memory()
+    }
+
+    pub fn reads_memory(&self) -> bool {
+        self.comp.uses_m()
+    }
+
+    pub fn writes_memory(&self) -> bool {
+        self.dest.is_some_and(|dest| dest.writes_memory())
+    }
+
+    pub fn jumps(&self) -> bool {
+        self.jump.is_some()
+    }
+
+    // unconditional jumps, including conditions that hold for a constant computation
+    pub fn always_jumps(&self) -> bool {
+        let value: i16 = match self.comp {
+            Comp::Zero => 0,
+            Comp::One => 1,
+            Comp::MinusOne => -1,
+            _ => return self.jump == Some(token::TokenKind::Jmp),
+        };
+        match self.jump {
+            Some(token::TokenKind::Jgt) => value > 0,
+            Some(token::TokenKind::Jeq) => value == 0,
+            Some(token::TokenKind::Jge) => value >= 0,
+            Some(token::TokenKind::Jlt) => value < 0,
+            Some(token::TokenKind::Jne) => value != 0,
+            Some(token::TokenKind::Jle) => value <= 0,
+            Some(token::TokenKind::Jmp) => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -56,6 +173,19 @@ pub enum Comp {
     MMinusD,
     DAndM,
     DOrM,
+
+    #[cfg(feature = "extended-isa")]
+    DShiftLeft,
+    #[cfg(feature = "extended-isa")]
+    DShiftRight,
+    #[cfg(feature = "extended-isa")]
+    AShiftLeft,
+    #[cfg(feature = "extended-isa")]
+    AShiftRight,
+    #[cfg(feature = "extended-isa")]
+    MShiftLeft,
+    #[cfg(feature = "extended-isa")]
+    MShiftRight,
 }
 
 impl Comp {
@@ -92,12 +222,87 @@ impl Comp {
             Comp::MMinusD => "1000111",
             Comp::DAndM => "1000000",
             Comp::DOrM => "1010101",
+            // shifts reuse the computation bits to select the shifted register
+            #[cfg(feature = "extended-isa")]
+            Comp::DShiftLeft | Comp::DShiftRight => "0001100",
+            #[cfg(feature = "extended-isa")]
+            Comp::AShiftLeft | Comp::AShiftRight => "0110000",
+            #[cfg(feature = "extended-isa")]
+            Comp::MShiftLeft | Comp::MShiftRight => "1110000",
         }
         .to_string()
     }
 
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Comp::Zero => "0",
+            Comp::One => "1",
+            Comp::MinusOne => "-1",
+            Comp::D => "D",
+            Comp::A => "A",
+            Comp::NotD => "!D",
+            Comp::NotA => "!A",
+            Comp::MinusD => "-D",
+            Comp::MinusA => "-A",
+            Comp::DPlusOne => "D+1",
+            Comp::APlusOne => "A+1",
+            Comp::DMinusOne => "D-1",
+            Comp::AMinusOne => "A-1",
+            Comp::DPlusA => "D+A",
+            Comp::DMinusA => "D-A",
+            Comp::AMinusD => "A-D",
+            Comp::DAndA => "D&A",
+            Comp::DOrA => "D|A",
+            Comp::M => "M",
+            Comp::NotM => "!M",
+            Comp::MinusM => "-M",
+            Comp::MPlusOne => "M+1",
+            Comp::MMinusOne => "M-1",
+            Comp::DPlusM => "D+M",
+            Comp::DMinusM => "D-M",
+            Comp::MMinusD => "M-D",
+            Comp::DAndM => "D&M",
+            Comp::DOrM => "D|M",
+            #[cfg(feature = "extended-isa")]
+            Comp::DShiftLeft => "D<<",
+            #[cfg(feature = "extended-isa")]
+            Comp::DShiftRight => "D>>",
+            #[cfg(feature = "extended-isa")]
+            Comp::AShiftLeft => "A<<",
+            #[cfg(feature = "extended-isa")]
+            Comp::AShiftRight => "A>>",
+            #[cfg(feature = "extended-isa")]
+            Comp::MShiftLeft => "M<<",
+            #[cfg(feature = "extended-isa")]
+            Comp::MShiftRight => "M>>",
+        }
+    }
+
+    // bits 15-13, the extended ISA clears bit 14 for shifts and uses bit 13 as direction
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "extended-isa")]
+            Comp::DShiftLeft | Comp::AShiftLeft | Comp::MShiftLeft => "101",
+            #[cfg(feature = "extended-isa")]
+            Comp::DShiftRight | Comp::AShiftRight | Comp::MShiftRight => "100",
+            _ => "111",
+        }
+    }
+
     pub fn from_tokens(tokens: &[token::TokenKind]) -> Option<Self> {
         match tokens {
+            #[cfg(feature = "extended-isa")]
+            [token::TokenKind::D, token::TokenKind::ShiftLeft, ..] => Some(Comp::DShiftLeft),
+            #[cfg(feature = "extended-isa")]
+            [token::TokenKind::D, token::TokenKind::ShiftRight, ..] => Some(Comp::DShiftRight),
+            #[cfg(feature = "extended-isa")]
+            [token::TokenKind::A, token::TokenKind::ShiftLeft, ..] => Some(Comp::AShiftLeft),
+            #[cfg(feature = "extended-isa")]
+            [token::TokenKind::A, token::TokenKind::ShiftRight, ..] => Some(Comp::AShiftRight),
+            #[cfg(feature = "extended-isa")]
+            [token::TokenKind::M, token::TokenKind::ShiftLeft, ..] => Some(Comp::MShiftLeft),
+            #[cfg(feature = "extended-isa")]
+            [token::TokenKind::M, token::TokenKind::ShiftRight, ..] => Some(Comp::MShiftRight),
             [token::TokenKind::D, token::TokenKind::Or, token::TokenKind::A, ..] => {
                 Some(Comp::DOrA)
             }
@@ -165,6 +370,46 @@ impl Comp {
         }
     }
 
+    pub fn uses_a(&self) -> bool {
+        #[cfg(feature = "extended-isa")]
+        if matches!(self, Comp::AShiftLeft | Comp::AShiftRight) {
+            return true;
+        }
+        matches!(
+            self,
+            Comp::A
+                | Comp::NotA
+                | Comp::MinusA
+                | Comp::APlusOne
+                | Comp::AMinusOne
+                | Comp::DPlusA
+                | Comp::DMinusA
+                | Comp::AMinusD
+                | Comp::DAndA
+                | Comp::DOrA
+        )
+    }
+
+    pub fn uses_m(&self) -> bool {
+        #[cfg(feature = "extended-isa")]
+        if matches!(self, Comp::MShiftLeft | Comp::MShiftRight) {
+            return true;
+        }
+        matches!(
+            self,
+            Comp::M
+                | Comp::NotM
+                | Comp::MinusM
+                | Comp::MPlusOne
+                | Comp::MMinusOne
+                | Comp::DPlusM
+                | Comp::DMinusM
+                | Comp::MMinusD
+                | Comp::DAndM
+                | Comp::DOrM
+        )
+    }
+
     // fn check_comp(tokens: &[Token], st
 
     pub fn len(&self) -> usize {
@@ -177,6 +422,13 @@ impl Comp {
             | Comp::NotA
             | Comp::NotD
             | Comp::NotM => 2,
+            #[cfg(feature = "extended-isa")]
+            Comp::DShiftLeft
+            | Comp::DShiftRight
+            | Comp::AShiftLeft
+            | Comp::AShiftRight
+            | Comp::MShiftLeft
+            | Comp::MShiftRight => 2,
             Comp::APlusOne
             | Comp::AMinusOne
             | Comp::DPlusOne
@@ -197,6 +449,38 @@ impl Comp {
     }
 }
 impl<'a> HackInstruction<'a> {
+    pub fn address_mut(&mut self) -> Option<&mut AInstruction<'a>> {
+        match self {
+            HackInstruction::A(ins) | HackInstruction::Word(ins) => Some(ins),
+            HackInstruction::C(_) => None,
+        }
+    }
+
+    // canonical assembly text, like what a pseudo-instruction expanded to
+    pub fn to_asm(&self) -> String {
+        let value = |ins: &AInstruction| match ins {
+            AInstruction::Number(val) => val.to_string(),
+            AInstruction::Identifier(name) => name.to_string(),
+        };
+        match self {
+            HackInstruction::A(ins) => format!("@{}", value(ins)),
+            HackInstruction::Word(word) => format!(".word {}", value(word)),
+            HackInstruction::C(cinst) => {
+                let mut asm = String::new();
+                if let Some(dest) = &cinst.dest {
+                    asm.push_str(dest.mnemonic());
+                    asm.push('=');
+                }
+                asm.push_str(cinst.comp.mnemonic());
+                if let Some(jump) = &cinst.jump {
+                    asm.push(';');
+                    asm.push_str(jump.mnemonic());
+                }
+                asm
+            }
+        }
+    }
+
     pub fn to_binary(&self) -> String {
         match self {
             HackInstruction::A(ins) => match ins {
@@ -205,21 +489,27 @@ impl<'a> HackInstruction<'a> {
                 }
                 AInstruction::Number(val) => format!("0{:015b}", val),
             },
+            HackInstruction::Word(word) => match word {
+                AInstruction::Identifier(_) => {
+                    panic!("Internal error: cannot directly convert a word with an identifier to binary");
+                }
+                AInstruction::Number(val) => format!("{:016b}", val),
+            },
             HackInstruction::C(cinst) => {
                 let mut binary = String::with_capacity(16);
-                binary.push_str("111");
+                binary.push_str(cinst.comp.prefix());
                 binary.push_str(&cinst.comp.to_binary());
                 binary.push_str(
                     &cinst
                         .dest
                         .as_ref()
-                        .map_or(String::from("000"), |token| token.as_bin_code()),
+                        .map_or(String::from("000"), |kind| kind.as_bin_code()),
                 );
                 binary.push_str(
                     &cinst
                         .jump
                         .as_ref()
-                        .map_or(String::from("000"), |token| token.as_bin_code()),
+                        .map_or(String::from("000"), |kind| kind.as_bin_code()),
                 );
                 binary
             }
@@ -0,0 +1,124 @@
+use crate::{Error, Result};
+
+// the subset of TOML used by `hackasm.toml`: tables, and keys with booleans, integers or strings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Bool(bool),
+    Integer(i64),
+    Str(String),
+}
+
+#[derive(Debug)]
+pub struct Entry {
+    pub table: Option<String>,
+    pub key: String,
+    pub value: Value,
+    pub line: usize,
+}
+
+pub fn parse(text: &str, file: &str) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let mut table = None;
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .map(str::trim)
+                .filter(|name| is_bare_key(name))
+                .ok_or_else(|| error(file, line_number, "Expected table name like '[lints]'"))?;
+            table = Some(name.to_string());
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error(file, line_number, "Expected 'key = value'"))?;
+        let key = parse_key(key.trim())
+            .ok_or_else(|| error(file, line_number, &format!("Invalid key '{}'", key.trim())))?;
+        let value = parse_value(value.trim()).ok_or_else(|| {
+            error(
+                file,
+                line_number,
+                "Expected a boolean, integer or string value after '='",
+            )
+        })?;
+        if entries
+            .iter()
+            .any(|entry: &Entry| entry.table == table && entry.key == key)
+        {
+            return Err(error(
+                file,
+                line_number,
+                &format!("Duplicate key '{}'", key),
+            ));
+        }
+        entries.push(Entry {
+            table: table.clone(),
+            key,
+            value,
+            line: line_number,
+        });
+    }
+    Ok(entries)
+}
+
+pub fn error(file: &str, line: usize, message: &str) -> Error {
+    Error::from(format!("{}:{}: {}", file, line, message))
+}
+
+// a '#' inside a string doesn't start a comment
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (index, char) in line.char_indices() {
+        match char {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn parse_key(key: &str) -> Option<String> {
+    if let Some(quoted) = key.strip_prefix('"') {
+        quoted.strip_suffix('"').map(String::from)
+    } else {
+        is_bare_key(key).then(|| key.to_string())
+    }
+}
+
+fn parse_value(value: &str) -> Option<Value> {
+    match value {
+        "true" => return Some(Value::Bool(true)),
+        "false" => return Some(Value::Bool(false)),
+        _ => {}
+    }
+    if let Some(quoted) = value.strip_prefix('"') {
+        let string = quoted.strip_suffix('"')?;
+        return (!string.contains('"')).then(|| Value::Str(string.to_string()));
+    }
+    let digits = value.replace('_', "");
+    let (digits, negative) = match digits.strip_prefix('-') {
+        Some(digits) => (digits.to_string(), true),
+        None => (digits, false),
+    };
+    let parsed = if let Some(hex) = digits.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if let Some(binary) = digits.strip_prefix("0b") {
+        i64::from_str_radix(binary, 2).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(Value::Integer(if negative { -parsed } else { parsed }))
+}
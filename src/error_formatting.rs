@@ -4,15 +4,43 @@ pub struct ErrorFormatter;
 
 impl ErrorFormatter {
     pub fn gen_err(message: &str, source: &[&str], start: usize, len: usize, line: usize) -> Error {
-        let line_content = &source[line];
-        let highlight = Self::highlight(line_content, start, len, line + 1);
-        Error::from(format!("{}\n error: {}", highlight, message))
+        Error::from(Self::gen_diagnostic(
+            "error", message, source, start, len, line,
+        ))
+    }
+
+    pub fn gen_warning(
+        message: &str,
+        source: &[&str],
+        start: usize,
+        len: usize,
+        line: usize,
+    ) -> String {
+        Self::gen_diagnostic("warning", message, source, start, len, line)
     }
 
     pub fn err_from_token(msg: &str, source: &[&str], token: &Token) -> Error {
         Self::gen_err(msg, source, token.start, token.len(), token.line)
     }
 
+    // appends a line like ` note: ...` or ` help: ...` below a generated error
+    pub fn add_note(error: Error, kind: &str, note: &str) -> Error {
+        Error::from(format!("{}\n {}: {}", error, kind, note))
+    }
+
+    fn gen_diagnostic(
+        level: &str,
+        message: &str,
+        source: &[&str],
+        start: usize,
+        len: usize,
+        line: usize,
+    ) -> String {
+        let line_content = &source[line];
+        let highlight = Self::highlight(line_content, start, len, line + 1);
+        format!("{}\n {}: {}", highlight, level, message)
+    }
+
     fn highlight(line_content: &str, start: usize, len: usize, line: usize) -> String {
         let line_str = line.to_string();
         let padding_len = &line_str.len() + 3 + start;
@@ -1,27 +1,228 @@
+use std::cmp;
+use std::collections::HashSet;
 use std::fs;
+use std::path::Path;
+
+use crate::ast::{Program, SymbolKind};
+use crate::options::{OutputFormat, Radix, SymbolOrder};
+use crate::parser::Parser;
+use crate::{Error, Options, Result};
+
+mod array;
+mod binary;
+mod coe;
+pub mod ihex;
+mod listing;
+mod logisim;
+mod mif;
+mod readmem;
+mod source_map;
+mod symbols;
+
+// fixed width for binary and hex so columns line up
+fn format_word(word: u16, radix: Radix) -> String {
+    match radix {
+        Radix::Binary => format!("{:016b}", word),
+        Radix::Decimal => word.to_string(),
+        Radix::Hex => format!("{:04x}", word),
+    }
+}
+
+fn escape_json(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
 
-use crate::ast::HackInstruction;
-use crate::Result;
 pub struct HackCodeGenerator<'a> {
-    instructions: Vec<HackInstruction<'a>>,
+    program: Program<'a>,
+    source: &'a [&'a str],
+    options: &'a Options,
 }
 
 impl<'a> HackCodeGenerator<'a> {
-    const EXTENSION: &'static str = "hack";
-    pub fn new(instructions: Vec<HackInstruction<'a>>) -> Self {
-        HackCodeGenerator { instructions }
+    pub fn new(program: Program<'a>, source: &'a [&'a str], options: &'a Options) -> Self {
+        HackCodeGenerator {
+            program,
+            source,
+            options,
+        }
+    }
+
+    pub fn words(&self) -> Vec<u16> {
+        self.program
+            .instructions
+            .iter()
+            .map(|ins| u16::from_str_radix(&ins.to_binary(), 2).unwrap())
+            .collect()
+    }
+
+    // size of the memory that memory formats describe, just the program unless configured.
+    // FPGA tools reject empty memories, so an empty program still gets one word
+    fn depth(&self, words: &[u16]) -> Result<usize> {
+        let Some(depth) = self.options.depth else {
+            return Ok(cmp::max(words.len(), 1));
+        };
+        if depth == 0 {
+            return Err(Error::from("Depth must be at least 1 word"));
+        }
+        if depth > Parser::ROM_SIZE {
+            return Err(Error::from(format!(
+                "Depth {} is larger than ROM, which holds {} words",
+                depth,
+                Parser::ROM_SIZE
+            )));
+        }
+        if depth < words.len() {
+            return Err(Error::from(format!(
+                "Depth {} is smaller than the program, which is {} words long",
+                depth,
+                words.len()
+            )));
+        }
+        Ok(depth)
+    }
+
+    // memory formats fill the rest of the configured depth with zeros
+    fn padded_words(&self) -> Result<Vec<u16>> {
+        let mut words = self.words();
+        words.resize(self.depth(&words)?, 0);
+        Ok(words)
+    }
+
+    // the statement each word was assembled from
+    fn source_texts(&self) -> Vec<&'a str> {
+        self.program
+            .locations
+            .iter()
+            .map(|location| {
+                let line = self.source[location.line];
+                line[location.start..location.start + location.len].trim()
+            })
+            .collect()
+    }
+
+    fn readmem(&self, radix: Radix) -> Result<String> {
+        let markers: HashSet<usize> = if self.options.mem_addresses {
+            let labels = self.program.labels.iter();
+            std::iter::once(0)
+                .chain(labels.map(|label| label.address as usize))
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let comments = if self.options.mem_comments {
+            self.source_texts()
+        } else {
+            Vec::new()
+        };
+        let words = self.padded_words()?;
+        Ok(self.join_lines(&readmem::encode(&words, radix, &markers, &comments)))
+    }
+
+    // numeric labels have no name and are left out
+    fn symbols(&self, kind: SymbolKind) -> Vec<(&str, u16)> {
+        match kind {
+            SymbolKind::Label => self
+                .program
+                .labels
+                .iter()
+                .filter_map(|label| Some((label.name.as_deref()?, label.address)))
+                .collect(),
+            SymbolKind::Variable => self
+                .program
+                .variables
+                .iter()
+                .map(|variable| (variable.name.as_ref(), variable.address))
+                .collect(),
+            SymbolKind::Predefined => {
+                let addresses = Parser::get_default_ident_map();
+                Parser::PREDEFINED_IDENTS
+                    .iter()
+                    .map(|&name| (name, addresses[name]))
+                    .collect()
+            }
+            SymbolKind::Constant => self
+                .options
+                .defines
+                .iter()
+                .map(|(name, value)| (name.as_str(), *value))
+                .collect(),
+        }
+    }
+
+    // the program's own symbols, exported next to C and Rust arrays and in listings
+    fn symbol_groups(&self) -> Vec<(&str, Vec<(&str, u16)>)> {
+        vec![
+            ("labels", self.symbols(SymbolKind::Label)),
+            ("variables", self.symbols(SymbolKind::Variable)),
+            ("constants", self.symbols(SymbolKind::Constant)),
+        ]
+    }
+
+    pub fn gen_symbol_files(&self, source: &str) -> Result<Vec<String>> {
+        let kinds = [
+            SymbolKind::Predefined,
+            SymbolKind::Label,
+            SymbolKind::Variable,
+            SymbolKind::Constant,
+        ];
+        // ROM and RAM addresses would mix when sorting across kinds, so each kind is sorted alone
+        let mut symbols = Vec::new();
+        for kind in kinds {
+            let mut group = self.symbols(kind);
+            match self.options.symbol_order {
+                SymbolOrder::Address => group.sort_by_key(|&(name, address)| (address, name)),
+                SymbolOrder::Name => group.sort(),
+            }
+            symbols.extend(
+                group
+                    .into_iter()
+                    .map(|(name, address)| (kind, name, address)),
+            );
+        }
+
+        let mut paths = Vec::new();
+        if self.options.symbols {
+            let path = self.get_output_filename(source, "sym");
+            fs::write(&path, self.join_lines(&symbols::encode_text(&symbols)))?;
+            paths.push(path);
+        }
+        if self.options.symbols_json {
+            let path = self.get_output_filename(source, "sym.json");
+            fs::write(&path, self.join_lines(&symbols::encode_json(&symbols)))?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    fn array(&self, language: array::Language, source: &str) -> Result<String> {
+        let path = Path::new(source);
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        let lines = array::encode(&self.words(), language, &name, &file, &self.symbol_groups())?;
+        Ok(self.join_lines(&lines))
     }
 
     pub fn evaluate(&self) -> String {
-        let mut output = String::new();
-        for i in &self.instructions {
-            output.push_str(&i.to_binary());
-            output.push_str("\r\n");
+        let lines: Vec<String> = self
+            .program
+            .instructions
+            .iter()
+            .map(|i| i.to_binary())
+            .collect();
+        self.join_lines(&lines)
+    }
+
+    // text formats share the configured line ending and trailing newline
+    fn join_lines(&self, lines: &[String]) -> String {
+        let line_ending = self.options.line_ending.as_str();
+        let mut output = lines.join(line_ending);
+        if self.options.trailing_newline && !lines.is_empty() {
+            output.push_str(line_ending);
         }
         output
     }
 
-    fn get_output_filename(&self, mut filename: &str) -> String {
+    fn get_output_filename(&self, mut filename: &str, extension: &str) -> String {
         for (id, char) in filename.chars().rev().enumerate() {
             match char {
                 '.' => {
@@ -34,12 +235,66 @@ impl<'a> HackCodeGenerator<'a> {
             }
         }
 
-        format!("{}.{}", filename, Self::EXTENSION)
+        format!("{}.{}", filename, extension)
+    }
+
+    // the listing is written next to the output, whatever its format
+    pub fn gen_listing_file(&self, source: &str) -> Result<String> {
+        let expansions: Vec<Option<String>> = self
+            .program
+            .instructions
+            .iter()
+            .zip(&self.program.expanded)
+            .map(|(ins, &expanded)| expanded.then(|| ins.to_asm()))
+            .collect();
+        let lines = listing::encode(
+            self.source,
+            &self.words(),
+            &expansions,
+            &self.program.locations,
+            &self.program.labels,
+            &self.symbol_groups(),
+        );
+        let path = self.get_output_filename(source, "lst");
+        fs::write(&path, self.join_lines(&lines))?;
+        Ok(path)
     }
 
-    pub fn gen_output_file(self, source: &str) -> Result<String> {
-        let path = self.get_output_filename(source);
-        fs::write(&path, self.evaluate())?;
+    pub fn gen_source_map_file(&self, source: &str) -> Result<String> {
+        let lines = source_map::encode(source, self.source, &self.program.locations);
+        let path = self.get_output_filename(source, "map.json");
+        fs::write(&path, self.join_lines(&lines))?;
+        Ok(path)
+    }
+
+    pub fn gen_output_file(&self, source: &str) -> Result<String> {
+        let format = self.options.format;
+        let output = match format {
+            OutputFormat::Hack => self.evaluate().into_bytes(),
+            OutputFormat::Bin => binary::encode(&self.words(), self.options.endianness),
+            OutputFormat::IntelHex => self
+                .join_lines(&ihex::encode(&self.words(), self.options.endianness))
+                .into_bytes(),
+            OutputFormat::Logisim => self
+                .join_lines(&logisim::encode(&self.words()))
+                .into_bytes(),
+            OutputFormat::ReadMemB => self.readmem(Radix::Binary)?.into_bytes(),
+            OutputFormat::ReadMemH => self.readmem(Radix::Hex)?.into_bytes(),
+            OutputFormat::C => self.array(array::Language::C, source)?.into_bytes(),
+            OutputFormat::Rust => self.array(array::Language::Rust, source)?.into_bytes(),
+            OutputFormat::Mif => {
+                let words = self.words();
+                let depth = self.depth(&words)?;
+                let lines = mif::encode(&words, depth, self.options.radix);
+                self.join_lines(&lines).into_bytes()
+            }
+            OutputFormat::Coe => {
+                let lines = coe::encode(&self.padded_words()?, self.options.radix);
+                self.join_lines(&lines).into_bytes()
+            }
+        };
+        let path = self.get_output_filename(source, format.extension());
+        fs::write(&path, output)?;
         Ok(path)
     }
 }
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Language {
+    C,
+    Rust,
+}
+
+// symbols are prefixed with the array name so several programs can be embedded side by side
+pub fn encode(
+    words: &[u16],
+    language: Language,
+    name: &str,
+    file: &str,
+    symbol_groups: &[(&str, Vec<(&str, u16)>)],
+) -> Result<Vec<String>> {
+    let name = identifier(name);
+    let mut lines = vec![format!("// Assembled from {} by rusty_hack_asm", file)];
+    if let Language::C = language {
+        lines.push("#include <stdint.h>".to_string());
+    }
+
+    let mut identifiers: HashMap<String, &str> = HashMap::new();
+    for (group, symbols) in symbol_groups {
+        if symbols.is_empty() {
+            continue;
+        }
+        lines.push(String::new());
+        lines.push(format!("// {}", group));
+        for &(symbol, value) in symbols {
+            let constant = format!("{}_{}", name, identifier(symbol));
+            if let Some(other) = identifiers.insert(constant.clone(), symbol) {
+                return Err(Error::from(format!(
+                    "Symbols '{}' and '{}' both become '{}' in the generated source",
+                    other, symbol, constant
+                )));
+            }
+            lines.push(match language {
+                Language::C => format!("#define {} 0x{:04x}", constant, value),
+                Language::Rust => format!("pub const {}: u16 = 0x{:04x};", constant, value),
+            });
+        }
+    }
+
+    lines.push(String::new());
+    lines.push(match language {
+        Language::C => format!("const uint16_t {}[] = {{", name),
+        Language::Rust => format!("pub const {}: [u16; {}] = [", name, words.len()),
+    });
+    for chunk in words.chunks(8) {
+        let chunk: Vec<String> = chunk
+            .iter()
+            .map(|word| format!("0x{:04x},", word))
+            .collect();
+        lines.push(format!("    {}", chunk.join(" ")));
+    }
+    lines.push(match language {
+        Language::C => "};".to_string(),
+        Language::Rust => "];".to_string(),
+    });
+    Ok(lines)
+}
+
+// local labels like `MAIN.loop` and VM translator labels like `Main.fib$ret.1` contain `.` and `$`,
+// which neither language allows in identifiers. Constants are upper case by convention in both
+fn identifier(symbol: &str) -> String {
+    let identifier: String = symbol
+        .chars()
+        .map(|char| match char {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => char.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .collect();
+    if identifier.starts_with(|char: char| char.is_ascii_digit()) {
+        format!("_{}", identifier)
+    } else {
+        identifier
+    }
+}
@@ -0,0 +1,13 @@
+use crate::options::Endianness;
+
+// two bytes per word, without any header
+pub fn encode(words: &[u16], endianness: Endianness) -> Vec<u8> {
+    let mut output = Vec::with_capacity(words.len() * 2);
+    for &word in words {
+        match endianness {
+            Endianness::Big => output.extend_from_slice(&word.to_be_bytes()),
+            Endianness::Little => output.extend_from_slice(&word.to_le_bytes()),
+        }
+    }
+    output
+}
@@ -0,0 +1,18 @@
+use super::format_word;
+use crate::options::Radix;
+
+// Xilinx coefficient file, a comma separated vector ending in a semicolon
+pub fn encode(words: &[u16], radix: Radix) -> Vec<String> {
+    let radix_number = match radix {
+        Radix::Binary => 2,
+        Radix::Decimal => 10,
+        Radix::Hex => 16,
+    };
+    let mut lines = vec![format!("memory_initialization_radix={};", radix_number)];
+    lines.push("memory_initialization_vector=".to_string());
+    for (address, &word) in words.iter().enumerate() {
+        let separator = if address + 1 == words.len() { ';' } else { ',' };
+        lines.push(format!("{}{}", format_word(word, radix), separator));
+    }
+    lines
+}
@@ -0,0 +1,125 @@
+use crate::{options::Endianness, parser::Parser, Error, Result};
+
+const BYTES_PER_RECORD: usize = 16;
+const DATA: u8 = 0x00;
+const END_OF_FILE: u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const START_LINEAR_ADDRESS: u8 = 0x05;
+
+// byte addressed, each word takes two bytes in the given order
+pub fn encode(words: &[u16], endianness: Endianness) -> Vec<String> {
+    let bytes = super::binary::encode(words, endianness);
+    let mut records = Vec::new();
+    let mut upper_address = 0;
+    for (id, chunk) in bytes.chunks(BYTES_PER_RECORD).enumerate() {
+        let address = id * BYTES_PER_RECORD;
+        // only needed past 64K, which the Hack ROM never reaches
+        if address >> 16 != upper_address {
+            upper_address = address >> 16;
+            records.push(record(
+                EXTENDED_LINEAR_ADDRESS,
+                0,
+                &(upper_address as u16).to_be_bytes(),
+            ));
+        }
+        records.push(record(DATA, address as u16, chunk));
+    }
+    records.push(record(END_OF_FILE, 0, &[]));
+    records
+}
+
+fn record(kind: u8, address: u16, data: &[u8]) -> String {
+    let mut bytes = vec![data.len() as u8];
+    bytes.extend_from_slice(&address.to_be_bytes());
+    bytes.push(kind);
+    bytes.extend_from_slice(data);
+    bytes.push(checksum(&bytes));
+    let mut line = String::from(":");
+    for byte in bytes {
+        line.push_str(&format!("{:02X}", byte));
+    }
+    line
+}
+
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+        .wrapping_neg()
+}
+
+pub fn decode(text: &str, endianness: Endianness) -> Result<Vec<u16>> {
+    let mut memory: Vec<u8> = Vec::new();
+    let mut base = 0;
+    for (id, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |message: &str| Error::from(format!("Intel HEX line {}: {}", id + 1, message));
+        let hex = line
+            .strip_prefix(':')
+            .ok_or_else(|| error("Expected ':' at the start of the record"))?;
+        // also keeps the byte slicing below on char boundaries
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(error("Invalid hex digit"));
+        }
+        if !hex.len().is_multiple_of(2) || hex.len() < 10 {
+            return Err(error("Record is too short"));
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect::<Vec<u8>>();
+        let len = bytes[0] as usize;
+        if bytes.len() != len + 5 {
+            return Err(error("Record length doesn't match its byte count"));
+        }
+        if checksum(&bytes[..bytes.len() - 1]) != bytes[bytes.len() - 1] {
+            return Err(error("Checksum mismatch"));
+        }
+        let address = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+        let data = &bytes[4..4 + len];
+        match bytes[3] {
+            DATA => {
+                let start = base + address;
+                // also keeps a hostile base address from allocating gigabytes
+                if start + len > 2 * Parser::ROM_SIZE {
+                    return Err(error(&format!(
+                        "Data past the end of ROM, which holds {} words",
+                        Parser::ROM_SIZE
+                    )));
+                }
+                if memory.len() < start + len {
+                    memory.resize(start + len, 0);
+                }
+                memory[start..start + len].copy_from_slice(data);
+            }
+            END_OF_FILE => break,
+            EXTENDED_SEGMENT_ADDRESS if len == 2 => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as usize) << 4
+            }
+            EXTENDED_LINEAR_ADDRESS if len == 2 => {
+                base = (u16::from_be_bytes([data[0], data[1]]) as usize) << 16
+            }
+            EXTENDED_SEGMENT_ADDRESS | EXTENDED_LINEAR_ADDRESS => {
+                return Err(error("Address record needs two data bytes"))
+            }
+            // start address records don't matter for a ROM image
+            START_SEGMENT_ADDRESS | START_LINEAR_ADDRESS => {}
+            kind => return Err(error(&format!("Unknown record type {:02X}", kind))),
+        }
+    }
+    if !memory.len().is_multiple_of(2) {
+        memory.push(0);
+    }
+    Ok(memory
+        .chunks(2)
+        .map(|pair| match endianness {
+            Endianness::Big => u16::from_be_bytes([pair[0], pair[1]]),
+            Endianness::Little => u16::from_le_bytes([pair[0], pair[1]]),
+        })
+        .collect())
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::ast::{Label, Location};
+
+// every source line with the address and words assembled from it, then the symbol table.
+// Pseudo-instructions get a row of their own, followed by the instructions they expand to
+pub fn encode(
+    source: &[&str],
+    words: &[u16],
+    expansions: &[Option<String>],
+    locations: &[Location],
+    labels: &[Label],
+    symbol_groups: &[(&str, Vec<(&str, u16)>)],
+) -> Vec<String> {
+    let mut words_by_line: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (address, location) in locations.iter().enumerate() {
+        words_by_line
+            .entry(location.line)
+            .or_default()
+            .push(address);
+    }
+    let mut label_lines = HashMap::new();
+    for label in labels {
+        label_lines
+            .entry(label.location.line)
+            .or_insert(label.address);
+    }
+
+    let mut lines = vec![format!(
+        "{:>5}  {:4}  {:16}  {:4}  source",
+        "line", "addr", "binary", "hex"
+    )];
+    for (id, text) in source.iter().enumerate() {
+        let row = |first: bool, address: Option<usize>, word: Option<u16>, text: &str| {
+            let line = if first {
+                (id + 1).to_string()
+            } else {
+                String::new()
+            };
+            let address = address.map_or(String::new(), |address| format!("{:04x}", address));
+            let (binary, hex) = word.map_or((String::new(), String::new()), |word| {
+                (format!("{:016b}", word), format!("{:04x}", word))
+            });
+            let row = format!(
+                "{:>5}  {:4}  {:16}  {:4}  {}",
+                line, address, binary, hex, text
+            );
+            row.trim_end().to_string()
+        };
+        match words_by_line.get(&id) {
+            Some(addresses) if expansions[addresses[0]].is_some() => {
+                lines.push(row(true, Some(addresses[0]), None, text));
+                // expanded instructions line up under the statement
+                let start = locations[addresses[0]].start;
+                let indent: String = text[..start]
+                    .chars()
+                    .map(|char| if char == '\t' { '\t' } else { ' ' })
+                    .collect();
+                for &address in addresses {
+                    let expansion = expansions[address].as_deref().unwrap_or_default();
+                    let text = format!("{}  {}", indent, expansion);
+                    lines.push(row(false, Some(address), Some(words[address]), &text));
+                }
+            }
+            Some(addresses) => {
+                // words after the first, like those of `.word` lists, continue on their own rows
+                for (n, &address) in addresses.iter().enumerate() {
+                    let text = if n == 0 { text } else { "" };
+                    lines.push(row(n == 0, Some(address), Some(words[address]), text));
+                }
+            }
+            None => {
+                let address = label_lines.get(&id).map(|&address| address as usize);
+                lines.push(row(true, address, None, text));
+            }
+        }
+    }
+
+    lines.push(String::new());
+    lines.push("symbols".to_string());
+    for (group, symbols) in symbol_groups {
+        if symbols.is_empty() {
+            continue;
+        }
+        let mut symbols = symbols.clone();
+        symbols.sort();
+        lines.push(format!("  {}", group));
+        for (name, value) in symbols {
+            lines.push(format!("    {:04x}  {}", value, name));
+        }
+    }
+    lines
+}
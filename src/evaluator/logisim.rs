@@ -0,0 +1,28 @@
+const HEADER: &str = "v2.0 raw";
+const ENTRIES_PER_LINE: usize = 8;
+// `3*0` is shorter than `0 0 0`, shorter runs are written out
+const MIN_RUN: usize = 3;
+
+// Logisim's `v2.0 raw` image: hex words separated by spaces, repeats written as `N*value`
+pub fn encode(words: &[u16]) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut rest = words;
+    while let Some(&word) = rest.first() {
+        let run = rest.iter().take_while(|&&other| other == word).count();
+        if run >= MIN_RUN {
+            entries.push(format!("{}*{:x}", run, word));
+            rest = &rest[run..];
+        } else {
+            entries.push(format!("{:x}", word));
+            rest = &rest[1..];
+        }
+    }
+
+    let mut lines = vec![HEADER.to_string()];
+    lines.extend(
+        entries
+            .chunks(ENTRIES_PER_LINE)
+            .map(|chunk| chunk.join(" ")),
+    );
+    lines
+}
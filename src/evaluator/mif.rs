@@ -0,0 +1,30 @@
+use super::format_word;
+use crate::options::Radix;
+
+// Intel/Altera Memory Initialization File, addresses past the program are zero
+pub fn encode(words: &[u16], depth: usize, radix: Radix) -> Vec<String> {
+    let data_radix = match radix {
+        Radix::Binary => "BIN",
+        Radix::Decimal => "UNS",
+        Radix::Hex => "HEX",
+    };
+    let mut lines = vec![
+        "WIDTH=16;".to_string(),
+        format!("DEPTH={};", depth),
+        String::new(),
+        "ADDRESS_RADIX=HEX;".to_string(),
+        format!("DATA_RADIX={};", data_radix),
+        String::new(),
+        "CONTENT BEGIN".to_string(),
+    ];
+    for (address, &word) in words.iter().enumerate() {
+        lines.push(format!("\t{:04X} : {};", address, format_word(word, radix)));
+    }
+    match depth - words.len() {
+        0 => {}
+        1 => lines.push(format!("\t{:04X} : 0;", words.len())),
+        _ => lines.push(format!("\t[{:04X}..{:04X}] : 0;", words.len(), depth - 1)),
+    }
+    lines.push("END;".to_string());
+    lines
+}
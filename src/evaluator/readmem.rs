@@ -0,0 +1,27 @@
+use std::collections::HashSet;
+
+use super::format_word;
+use crate::options::Radix;
+
+// one word per line for Verilog's `$readmemb`/`$readmemh`, `@address` markers set the
+// address of the following words and `//` comments are ignored
+pub fn encode(
+    words: &[u16],
+    radix: Radix,
+    markers: &HashSet<usize>,
+    comments: &[&str],
+) -> Vec<String> {
+    let mut lines = Vec::with_capacity(words.len());
+    for (address, &word) in words.iter().enumerate() {
+        if markers.contains(&address) {
+            lines.push(format!("@{:04x}", address));
+        }
+        let mut line = format_word(word, radix);
+        if let Some(comment) = comments.get(address) {
+            line.push_str(" // ");
+            line.push_str(comment);
+        }
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use super::escape_json;
+use crate::ast::Location;
+
+// one entry per ROM word with the 1-based line and columns of the statement it came from,
+// `index` counts the words of statements that expand to several, like pseudo-instructions
+pub fn encode(file: &str, source: &[&str], locations: &[Location]) -> Vec<String> {
+    let mut lines = vec![
+        "{".to_string(),
+        format!("  \"file\": \"{}\",", escape_json(file)),
+        "  \"words\": [".to_string(),
+    ];
+    let mut indices: HashMap<(usize, usize), usize> = HashMap::new();
+    for (address, location) in locations.iter().enumerate() {
+        let index = indices.entry((location.line, location.start)).or_default();
+        // columns count characters rather than bytes
+        let text = source[location.line];
+        let column = text[..location.start].chars().count() + 1;
+        let end_column = column + text[location.start..][..location.len].chars().count();
+        let separator = if address + 1 == locations.len() {
+            ""
+        } else {
+            ","
+        };
+        lines.push(format!(
+            "    {{\"address\": {}, \"line\": {}, \"column\": {}, \"end_column\": {}, \
+             \"index\": {}}}{}",
+            address,
+            location.line + 1,
+            column,
+            end_column,
+            index,
+            separator
+        ));
+        *index += 1;
+    }
+    lines.push("  ]".to_string());
+    lines.push("}".to_string());
+    lines
+}
@@ -0,0 +1,28 @@
+use super::escape_json;
+use crate::ast::SymbolKind;
+
+// one symbol per line as `ADDRESS KIND NAME`, the address in decimal
+pub fn encode_text(symbols: &[(SymbolKind, &str, u16)]) -> Vec<String> {
+    let mut lines = vec!["# address kind name".to_string()];
+    for &(kind, name, address) in symbols {
+        lines.push(format!("{:<5} {:<10} {}", address, kind.name(), name));
+    }
+    lines
+}
+
+// an array of `{"name", "kind", "address"}` objects, one per line
+pub fn encode_json(symbols: &[(SymbolKind, &str, u16)]) -> Vec<String> {
+    let mut lines = vec!["[".to_string()];
+    for (id, &(kind, name, address)) in symbols.iter().enumerate() {
+        let separator = if id + 1 == symbols.len() { "" } else { "," };
+        lines.push(format!(
+            "  {{\"name\": \"{}\", \"kind\": \"{}\", \"address\": {}}}{}",
+            escape_json(name),
+            kind.name(),
+            address,
+            separator
+        ));
+    }
+    lines.push("]".to_string());
+    lines
+}
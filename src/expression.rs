@@ -0,0 +1,216 @@
+use std::cmp;
+
+use crate::scanner::token::{Token, TokenKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Negate,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    BitAnd,
+    BitOr,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn from_token(kind: TokenKind) -> Option<Self> {
+        match kind {
+            TokenKind::Plus => Some(BinaryOp::Add),
+            TokenKind::Minus => Some(BinaryOp::Sub),
+            TokenKind::And => Some(BinaryOp::BitAnd),
+            TokenKind::Or => Some(BinaryOp::BitOr),
+            TokenKind::EqualEqual => Some(BinaryOp::Equal),
+            TokenKind::NotEqual => Some(BinaryOp::NotEqual),
+            TokenKind::Less => Some(BinaryOp::Less),
+            TokenKind::LessEqual => Some(BinaryOp::LessEqual),
+            TokenKind::Greater => Some(BinaryOp::Greater),
+            TokenKind::GreaterEqual => Some(BinaryOp::GreaterEqual),
+            TokenKind::AndAnd => Some(BinaryOp::And),
+            TokenKind::OrOr => Some(BinaryOp::Or),
+            _ => None,
+        }
+    }
+
+    // higher binds tighter. Like in Rust but unlike C, `&` and `|` bind tighter than comparisons,
+    // so `FLAGS & 4 == 4` tests a bit
+    fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Equal
+            | BinaryOp::NotEqual
+            | BinaryOp::Less
+            | BinaryOp::LessEqual
+            | BinaryOp::Greater
+            | BinaryOp::GreaterEqual => 3,
+            BinaryOp::BitOr => 4,
+            BinaryOp::BitAnd => 5,
+            BinaryOp::Add | BinaryOp::Sub => 6,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitOr => "|",
+            BinaryOp::Equal => "==",
+            BinaryOp::NotEqual => "!=",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+        }
+    }
+
+    pub fn apply(&self, left: i64, right: i64) -> i64 {
+        match self {
+            BinaryOp::Add => left.wrapping_add(right),
+            BinaryOp::Sub => left.wrapping_sub(right),
+            BinaryOp::BitAnd => left & right,
+            BinaryOp::BitOr => left | right,
+            BinaryOp::Equal => (left == right) as i64,
+            BinaryOp::NotEqual => (left != right) as i64,
+            BinaryOp::Less => (left < right) as i64,
+            BinaryOp::LessEqual => (left <= right) as i64,
+            BinaryOp::Greater => (left > right) as i64,
+            BinaryOp::GreaterEqual => (left >= right) as i64,
+            BinaryOp::And => (left != 0 && right != 0) as i64,
+            BinaryOp::Or => (left != 0 || right != 0) as i64,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr<'a> {
+    Number(i64),
+    Symbol(Token<'a>),
+    Unary(UnaryOp, Box<Expr<'a>>),
+    Binary(BinaryOp, Box<Expr<'a>>, Box<Expr<'a>>),
+}
+
+pub struct ExprError<'a> {
+    pub message: String,
+    pub token: Token<'a>,
+}
+
+impl<'a> Expr<'a> {
+    pub fn parse(tokens: &[Token<'a>]) -> Result<(Self, usize), ExprError<'a>> {
+        let mut parser = ExprParser { tokens, curr: 0 };
+        let expr = parser.binary(0)?;
+        Ok((expr, parser.curr))
+    }
+
+    pub fn eval<F>(&self, lookup: &F) -> Result<i64, ExprError<'a>>
+    where
+        F: Fn(&str) -> Option<i64>,
+    {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Symbol(token) => match token.kind {
+                TokenKind::Identifier(name) => lookup(name).ok_or_else(|| ExprError {
+                    message: format!("Unknown symbol '{}' in expression", name),
+                    token: *token,
+                }),
+                _ => unreachable!(),
+            },
+            Expr::Unary(op, operand) => {
+                let value = operand.eval(lookup)?;
+                Ok(match op {
+                    UnaryOp::Negate => value.wrapping_neg(),
+                    UnaryOp::Not => (value == 0) as i64,
+                })
+            }
+            Expr::Binary(op, left, right) => Ok(op.apply(left.eval(lookup)?, right.eval(lookup)?)),
+        }
+    }
+
+    pub fn symbols(&self, out: &mut Vec<Token<'a>>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Symbol(token) => out.push(*token),
+            Expr::Unary(_, operand) => operand.symbols(out),
+            Expr::Binary(_, left, right) => {
+                left.symbols(out);
+                right.symbols(out);
+            }
+        }
+    }
+}
+
+struct ExprParser<'a, 't> {
+    tokens: &'t [Token<'a>],
+    curr: usize,
+}
+
+impl<'a, 't> ExprParser<'a, 't> {
+    fn peek(&self) -> Token<'a> {
+        // the token stream always ends with Eof, so stay on it
+        self.tokens[cmp::min(self.curr, self.tokens.len() - 1)]
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr<'a>, ExprError<'a>> {
+        let mut left = self.unary()?;
+        while let Some(op) = BinaryOp::from_token(self.peek().kind) {
+            if op.precedence() <= min_precedence {
+                break;
+            }
+            self.curr += 1;
+            let right = self.binary(op.precedence())?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr<'a>, ExprError<'a>> {
+        let op = match self.peek().kind {
+            TokenKind::Minus => UnaryOp::Negate,
+            TokenKind::Not => UnaryOp::Not,
+            _ => return self.primary(),
+        };
+        self.curr += 1;
+        Ok(Expr::Unary(op, Box::new(self.unary()?)))
+    }
+
+    fn primary(&mut self) -> Result<Expr<'a>, ExprError<'a>> {
+        let token = self.peek();
+        let expr = match token.kind {
+            TokenKind::Number(n, _) => Expr::Number(n as i64),
+            TokenKind::Identifier(_) => Expr::Symbol(token),
+            TokenKind::LeftParen => {
+                self.curr += 1;
+                let inner = self.binary(0)?;
+                if self.peek().kind != TokenKind::RightParen {
+                    return Err(ExprError {
+                        message: "Expected ')' after expression".to_string(),
+                        token: self.peek(),
+                    });
+                }
+                inner
+            }
+            _ => {
+                return Err(ExprError {
+                    message: "Expected number, symbol or '(' in expression".to_string(),
+                    token,
+                })
+            }
+        };
+        self.curr += 1;
+        Ok(expr)
+    }
+}
@@ -1,17 +1,44 @@
 use std::{fs, time::Instant};
 
-use crate::{evaluator::HackCodeGenerator, parser::Parser, scanner::Scanner};
+use crate::{
+    evaluator::HackCodeGenerator, parser::Parser, preprocessor::Preprocessor, scanner::Scanner,
+};
+
+pub use crate::lints::LintLevel;
+pub use crate::options::{
+    parse_number, Endianness, LineEnding, Options, OutputFormat, Radix, SymbolOrder,
+};
 
 mod ast;
+mod config;
 mod error_formatting;
 mod evaluator;
+mod expression;
+mod lints;
+mod options;
 mod parser;
+mod preprocessor;
 mod scanner;
+mod suggestions;
 
 pub type Error = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Error>;
 
+// reads a ROM image written with `--format=ihex` back into words
+pub fn read_intel_hex(text: &str, endianness: Endianness) -> Result<Vec<u16>> {
+    evaluator::ihex::decode(text, endianness)
+}
+
+// uses the `hackasm.toml` next to the source file, if there is one
 pub fn run(path: String) -> Result<()> {
+    let mut options = Options::default();
+    if let Some(config) = Options::config_path(&path) {
+        options.load_config(&config)?;
+    }
+    run_with_options(path, &options)
+}
+
+pub fn run_with_options(path: String, options: &Options) -> Result<()> {
     let t = Instant::now();
 
     let source = fs::read_to_string(&path)?;
@@ -22,17 +49,36 @@ pub fn run(path: String) -> Result<()> {
     #[cfg(feature = "measure")]
     let parse_time = Instant::now();
 
-    let tokens = scanner.run()?;
-    let parser = Parser::new(&tokens, &source);
-    let instructions = parser.run()?;
+    let (tokens, suppressions) = scanner.run()?;
+    let tokens = Preprocessor::new(tokens, &source, &options.defines).run()?;
+    let parser = Parser::new(&tokens, &source, options);
+    let mut program = parser.run()?;
 
     #[cfg(feature = "measure")]
     println!("Parsing took {:?}", parse_time.elapsed());
 
-    let evaluator = HackCodeGenerator::new(instructions);
+    let mut warnings = lints::check(&program);
+    warnings.append(&mut program.warnings);
+    lints::report(warnings, &suppressions, &source, options)?;
+
+    let size = program.instructions.len();
+    let evaluator = HackCodeGenerator::new(program, &source, options);
 
     let output = evaluator.gen_output_file(&path)?;
+    if options.listing {
+        evaluator.gen_listing_file(&path)?;
+    }
+    evaluator.gen_symbol_files(&path)?;
+    if options.source_map {
+        evaluator.gen_source_map_file(&path)?;
+    }
 
-    println!("Succesfully compiled '{}' in {:?}", output, t.elapsed());
+    println!(
+        "Succesfully compiled '{}' ({} of {} ROM words) in {:?}",
+        output,
+        size,
+        Parser::ROM_SIZE,
+        t.elapsed()
+    );
     Ok(())
 }
@@ -0,0 +1,244 @@
+use crate::{
+    ast::{Location, Program},
+    error_formatting::ErrorFormatter,
+    suggestions, Error, Options, Result,
+};
+
+mod control_flow;
+mod hazards;
+mod memory_map;
+mod symbols;
+mod uninitialised;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    SimilarName,
+    JumpWritesA,
+    JumpAccessesMemory,
+    UnreachableCode,
+    UnusedLabel,
+    FallOffEnd,
+    LabelAsVariable,
+    VariableAsLabel,
+    UnmappedMemory,
+    KeyboardWrite,
+    ScreenVariable,
+    MagicAddress,
+    UninitialisedRead,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 13] = [
+        Lint::SimilarName,
+        Lint::JumpWritesA,
+        Lint::JumpAccessesMemory,
+        Lint::UnreachableCode,
+        Lint::UnusedLabel,
+        Lint::FallOffEnd,
+        Lint::LabelAsVariable,
+        Lint::VariableAsLabel,
+        Lint::UnmappedMemory,
+        Lint::KeyboardWrite,
+        Lint::ScreenVariable,
+        Lint::MagicAddress,
+        Lint::UninitialisedRead,
+    ];
+    // selects every lint at once, like `--deny warnings`
+    pub const GROUP_ALL: &'static str = "warnings";
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::SimilarName => "similar-name",
+            Lint::JumpWritesA => "jump-writes-a",
+            Lint::JumpAccessesMemory => "jump-accesses-memory",
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::UnusedLabel => "unused-label",
+            Lint::FallOffEnd => "fall-off-end",
+            Lint::LabelAsVariable => "label-as-variable",
+            Lint::VariableAsLabel => "variable-as-label",
+            Lint::UnmappedMemory => "unmapped-memory",
+            Lint::KeyboardWrite => "keyboard-write",
+            Lint::ScreenVariable => "screen-variable",
+            Lint::MagicAddress => "magic-address",
+            Lint::UninitialisedRead => "uninitialised-read",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintLevel {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+// `// hackasm: allow(LINT, ...)` silences lints on the statement it is attached to,
+// either the statement on the same line or the next one
+#[derive(Debug)]
+pub struct Suppression<'a> {
+    pub lints: Vec<&'a str>,
+    pub line: usize,
+    pub location: Location,
+}
+
+impl Suppression<'_> {
+    fn allows(&self, warning: &Warning) -> bool {
+        warning.location.line == self.line
+            && self
+                .lints
+                .iter()
+                .any(|&name| name == warning.lint.name() || name == Lint::GROUP_ALL)
+    }
+}
+
+#[derive(Debug)]
+pub struct Warning {
+    pub lint: Lint,
+    pub message: String,
+    pub location: Location,
+    pub help: Option<String>,
+}
+
+impl Warning {
+    pub fn new(lint: Lint, message: String, location: Location) -> Self {
+        Self {
+            lint,
+            message,
+            location,
+            help: None,
+        }
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    pub fn format(&self, source: &[&str]) -> String {
+        let mut formatted = ErrorFormatter::gen_warning(
+            &format!("{} [{}]", self.message, self.lint.name()),
+            source,
+            self.location.start,
+            self.location.len,
+            self.location.line,
+        );
+        if let Some(help) = &self.help {
+            formatted.push_str(&format!("\n help: {}", help));
+        }
+        formatted
+    }
+
+    pub fn to_error(&self, source: &[&str]) -> Error {
+        let mut error = ErrorFormatter::gen_err(
+            &format!("{} [{}]", self.message, self.lint.name()),
+            source,
+            self.location.start,
+            self.location.len,
+            self.location.line,
+        );
+        if let Some(help) = &self.help {
+            error = ErrorFormatter::add_note(error, "help", help);
+        }
+        ErrorFormatter::add_note(error, "note", &format!("`{}` is denied", self.lint.name()))
+    }
+}
+
+// semantic checks on the assembled program, the parser reports its own warnings while parsing
+pub fn check(program: &Program) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    hazards::check(program, &mut warnings);
+    control_flow::check(program, &mut warnings);
+    symbols::check(program, &mut warnings);
+    memory_map::check(program, &mut warnings);
+    uninitialised::check(program, &mut warnings);
+    warnings
+}
+
+// prints allowed warnings and fails if any of them is denied
+pub fn report(
+    mut warnings: Vec<Warning>,
+    suppressions: &[Suppression],
+    source: &[&str],
+    options: &Options,
+) -> Result<()> {
+    check_suppressions(suppressions, source)?;
+    warnings.retain(|warning| {
+        options.lint_level(warning.lint) != LintLevel::Allow
+            && !suppressions
+                .iter()
+                .any(|suppression| suppression.allows(warning))
+    });
+    warnings.sort_by_key(|warning| (warning.location.line, warning.location.start));
+    let (denied, warnings): (Vec<_>, Vec<_>) = warnings
+        .into_iter()
+        .partition(|warning| options.lint_level(warning.lint) == LintLevel::Deny);
+
+    for warning in &warnings {
+        eprintln!("{}\n", warning.format(source));
+    }
+    if !warnings.is_empty() {
+        eprintln!("Emitted {} warnings", warnings.len());
+    }
+    for warning in &denied {
+        eprintln!("{}", warning.to_error(source));
+    }
+    if denied.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::from(format!(
+            "Encountered {} errors, aborting compilation",
+            denied.len()
+        )))
+    }
+}
+
+fn check_suppressions(suppressions: &[Suppression], source: &[&str]) -> Result<()> {
+    let mut errors = 0;
+    for suppression in suppressions {
+        for &name in &suppression.lints {
+            if name == Lint::GROUP_ALL || Lint::from_name(name).is_some() {
+                continue;
+            }
+            let location = suppression.location;
+            let mut error = ErrorFormatter::gen_err(
+                &format!("Unknown lint '{}' in suppression", name),
+                source,
+                location.start,
+                location.len,
+                location.line,
+            );
+            if let Some(similar) = suggestions::closest(name, Lint::ALL.map(|lint| lint.name())) {
+                error = ErrorFormatter::add_note(
+                    error,
+                    "help",
+                    &format!("did you mean `{}`?", similar),
+                );
+            }
+            eprintln!("{}", error);
+            errors += 1;
+        }
+    }
+    if errors == 0 {
+        Ok(())
+    } else {
+        Err(Error::from(format!(
+            "Encountered {} errors, aborting compilation",
+            errors
+        )))
+    }
+}
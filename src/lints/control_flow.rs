@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use super::{Lint, Warning};
+use crate::ast::{AInstruction, HackInstruction, Program, SymbolKind};
+
+pub fn check(program: &Program, warnings: &mut Vec<Warning>) {
+    check_reachability(program, warnings);
+    check_unused_labels(program, warnings);
+}
+
+// code is entered at address 0, at labels and at constants that can be jump targets,
+// every other instruction has to be reached by falling through from the one before.
+// Constants used as a value, like return addresses in `@RET D=A` of compiled VM code, may be
+// jumped to later. They only count when they point right after an unconditional jump,
+// where a return address lands, so that small numbers don't hide unrelated code
+fn entry_points(program: &Program) -> Vec<bool> {
+    let instructions = &program.instructions;
+    let mut entries = vec![false; instructions.len()];
+    if let Some(first) = entries.first_mut() {
+        *first = true;
+    }
+    for label in &program.labels {
+        if let Some(entry) = entries.get_mut(label.address as usize) {
+            *entry = true;
+        }
+    }
+    for pair in instructions.windows(2) {
+        if let [HackInstruction::A(AInstruction::Number(target)), HackInstruction::C(ins)] = pair {
+            let target = *target as usize;
+            let after_jump = || {
+                target > 0
+                    && matches!(
+                        instructions.get(target - 1),
+                        Some(HackInstruction::C(before)) if before.always_jumps()
+                    )
+            };
+            if ins.jumps() || (ins.reads_a() && after_jump()) {
+                if let Some(entry) = entries.get_mut(target) {
+                    *entry = true;
+                }
+            }
+        }
+    }
+    entries
+}
+
+fn check_reachability(program: &Program, warnings: &mut Vec<Warning>) {
+    let entries = entry_points(program);
+    let mut reachable = false;
+    let mut unreachable_run: Option<(usize, usize)> = None;
+    let mut last_code = None;
+    for (id, ins) in program.instructions.iter().enumerate() {
+        reachable |= entries[id];
+        // data words after a jump are expected, so they end a run of unreachable code
+        if let HackInstruction::Word(_) = ins {
+            report_unreachable(program, unreachable_run.take(), warnings);
+            continue;
+        }
+        if reachable {
+            report_unreachable(program, unreachable_run.take(), warnings);
+        } else {
+            unreachable_run.get_or_insert((id, 0)).1 += 1;
+        }
+        last_code = Some((id, reachable));
+        if let HackInstruction::C(ins) = ins {
+            if ins.always_jumps() {
+                reachable = false;
+            }
+        }
+    }
+    report_unreachable(program, unreachable_run, warnings);
+
+    if let Some((id, true)) = last_code {
+        if !matches!(&program.instructions[id], HackInstruction::C(ins) if ins.always_jumps()) {
+            warnings.push(
+                Warning::new(
+                    Lint::FallOffEnd,
+                    "Execution continues past the last instruction".to_string(),
+                    program.locations[id],
+                )
+                .with_help(
+                    "end the program with an infinite loop like `(END) @END 0;JMP`".to_string(),
+                ),
+            );
+        }
+    }
+}
+
+// without any labels, like compiled VM code with labels stripped, every function is only
+// entered through computed jumps, so there is no telling dead code from live code
+fn report_unreachable(program: &Program, run: Option<(usize, usize)>, warnings: &mut Vec<Warning>) {
+    let Some((first, count)) = run else {
+        return;
+    };
+    if program.labels.is_empty() {
+        return;
+    }
+    let message = if count == 1 {
+        "Unreachable instruction after an unconditional jump".to_string()
+    } else {
+        format!(
+            "{} unreachable instructions after an unconditional jump",
+            count
+        )
+    };
+    warnings.push(
+        Warning::new(Lint::UnreachableCode, message, program.locations[first])
+            .with_help("add a label if the code is the target of a computed jump".to_string()),
+    );
+}
+
+fn check_unused_labels(program: &Program, warnings: &mut Vec<Warning>) {
+    let referenced: HashSet<&str> = program
+        .references
+        .iter()
+        .filter(|reference| reference.kind == SymbolKind::Label)
+        .map(|reference| &*reference.name)
+        .collect();
+    for label in &program.labels {
+        if let Some(name) = &label.name {
+            if !referenced.contains(&**name) {
+                warnings.push(Warning::new(
+                    Lint::UnusedLabel,
+                    format!("Label {} is never referenced", name),
+                    label.location,
+                ));
+            }
+        }
+    }
+}
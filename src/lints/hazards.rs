@@ -0,0 +1,36 @@
+use super::{Lint, Warning};
+use crate::ast::{HackInstruction, Program};
+
+// a jump always goes to the value A had before the instruction, and RAM[A] is the
+// same register, so one instruction can't both address memory and a jump target
+pub fn check(program: &Program, warnings: &mut Vec<Warning>) {
+    for (ins, &location) in program.instructions.iter().zip(&program.locations) {
+        let HackInstruction::C(ins) = ins else {
+            continue;
+        };
+        if !ins.jumps() {
+            continue;
+        }
+        let warning = if ins.writes_a() {
+            Warning::new(
+                Lint::JumpWritesA,
+                "Instruction writes A and jumps, the jump target is the old value of A".to_string(),
+                location,
+            )
+            .with_help("load the jump target into A in a separate instruction".to_string())
+        } else if ins.accesses_memory() {
+            Warning::new(
+                Lint::JumpAccessesMemory,
+                "Instruction uses M and jumps, A is both the memory address and the jump target"
+                    .to_string(),
+                location,
+            )
+            .with_help(
+                "split the memory access and the jump into separate instructions".to_string(),
+            )
+        } else {
+            continue;
+        };
+        warnings.push(warning);
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use super::{Lint, Warning};
+use crate::ast::{AInstruction, HackInstruction, Program, SymbolKind};
+
+const SCREEN: u16 = 16384;
+const KBD: u16 = 24576;
+
+pub fn check(program: &Program, warnings: &mut Vec<Warning>) {
+    check_addresses(program, warnings);
+    check_variables(program, warnings);
+}
+
+fn check_addresses(program: &Program, warnings: &mut Vec<Warning>) {
+    let symbols: HashMap<usize, SymbolKind> = program
+        .references
+        .iter()
+        .filter_map(|reference| Some((reference.instruction?, reference.kind)))
+        .collect();
+    for (id, pair) in program.instructions.windows(2).enumerate() {
+        let [HackInstruction::A(AInstruction::Number(address)), HackInstruction::C(next)] = pair
+        else {
+            continue;
+        };
+        let address = *address;
+        let location = program.locations[id];
+        let symbol = symbols.get(&id).copied();
+        // addresses of labels are checked by `label-as-variable`
+        if next.accesses_memory() && address > KBD && symbol != Some(SymbolKind::Label) {
+            warnings.push(Warning::new(
+                Lint::UnmappedMemory,
+                format!(
+                    "Address {} is past the end of RAM, the last register is KBD at {}",
+                    address, KBD
+                ),
+                location,
+            ));
+        }
+        if next.writes_memory() && address == KBD && symbol != Some(SymbolKind::Label) {
+            warnings.push(Warning::new(
+                Lint::KeyboardWrite,
+                "Writing to KBD has no effect, the keyboard register is read-only".to_string(),
+                location,
+            ));
+        }
+        if symbol.is_none() {
+            let predefined = match address {
+                0..=15 if next.accesses_memory() => format!("R{}", address),
+                SCREEN if next.accesses_memory() || next.reads_a() => "SCREEN".to_string(),
+                KBD if next.accesses_memory() || next.reads_a() => "KBD".to_string(),
+                _ => continue,
+            };
+            warnings.push(
+                Warning::new(
+                    Lint::MagicAddress,
+                    format!(
+                        "Address {} is the predefined symbol {}",
+                        address, predefined
+                    ),
+                    location,
+                )
+                .with_help(format!("use `@{}` to make the address clearer", predefined)),
+            );
+        }
+    }
+}
+
+fn check_variables(program: &Program, warnings: &mut Vec<Warning>) {
+    for variable in program
+        .variables
+        .iter()
+        .filter(|variable| variable.allocated)
+    {
+        let region = match variable.address {
+            SCREEN..KBD => "in screen memory",
+            KBD => "at KBD",
+            address if address > KBD => "past the end of RAM",
+            _ => continue,
+        };
+        warnings.push(
+            Warning::new(
+                Lint::ScreenVariable,
+                format!(
+                    "Variable {} is allocated to address {} {}",
+                    variable.name, variable.address, region
+                ),
+                variable.location,
+            )
+            .with_help(format!(
+                "keep `--var-limit` below {} or place the variable with `.var`",
+                SCREEN
+            )),
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use super::{Lint, Warning};
+use crate::{
+    ast::{HackInstruction, Program, SymbolKind},
+    suggestions,
+};
+
+// labels are ROM addresses and variables RAM addresses, so a label used through M or a
+// variable used as a jump target is almost always a misspelled or confused symbol
+pub fn check(program: &Program, warnings: &mut Vec<Warning>) {
+    for reference in &program.references {
+        let Some(id) = reference.instruction else {
+            continue;
+        };
+        let (HackInstruction::A(_), Some(HackInstruction::C(next))) =
+            (&program.instructions[id], program.instructions.get(id + 1))
+        else {
+            continue;
+        };
+        let (lint, message, candidates): (_, _, Vec<&str>) = match reference.kind {
+            SymbolKind::Label if next.accesses_memory() => (
+                Lint::LabelAsVariable,
+                format!(
+                    "Label {} is a ROM address, but M accesses RAM at that address",
+                    reference.name
+                ),
+                program
+                    .references
+                    .iter()
+                    .filter(|other| other.kind == SymbolKind::Variable)
+                    .map(|other| &*other.name)
+                    .collect(),
+            ),
+            SymbolKind::Variable if next.jumps() => (
+                Lint::VariableAsLabel,
+                format!(
+                    "Variable {} is a RAM address, but it is used as a jump target",
+                    reference.name
+                ),
+                program
+                    .labels
+                    .iter()
+                    .filter_map(|label| label.name.as_deref())
+                    .collect(),
+            ),
+            _ => continue,
+        };
+        let help = match suggestions::closest(&reference.name, candidates) {
+            Some(similar) => format!("did you mean `{}`?", similar),
+            None if lint == Lint::VariableAsLabel => {
+                format!("declare a label `({})` to jump to it", reference.name)
+            }
+            None => "use A for the label's address, M reads and writes RAM".to_string(),
+        };
+        warnings.push(Warning::new(lint, message, reference.location).with_help(help));
+    }
+}
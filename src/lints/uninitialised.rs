@@ -0,0 +1,132 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{Lint, Warning};
+use crate::ast::{AInstruction, HackInstruction, Program, Variable};
+
+// what is known before an instruction executes, on every path that reaches it
+#[derive(Clone, PartialEq)]
+struct State {
+    written: Vec<bool>,
+    a: Option<u16>,
+}
+
+impl State {
+    fn join(&mut self, other: &State) -> bool {
+        let mut changed = false;
+        for (written, &other) in self.written.iter_mut().zip(&other.written) {
+            if *written && !other {
+                *written = false;
+                changed = true;
+            }
+        }
+        if self.a.is_some() && self.a != other.a {
+            self.a = None;
+            changed = true;
+        }
+        changed
+    }
+}
+
+// RAM is not cleared on real hardware, so every variable has to be written before it is
+// read. This is a must-analysis: a variable counts as written only if it is on all paths
+pub fn check(program: &Program, warnings: &mut Vec<Warning>) {
+    let allocated: Vec<&Variable> = program
+        .variables
+        .iter()
+        .filter(|variable| variable.allocated)
+        .collect();
+    let variables: HashMap<u16, usize> = allocated
+        .iter()
+        .enumerate()
+        .map(|(index, variable)| (variable.address, index))
+        .collect();
+    if variables.is_empty() || program.instructions.is_empty() {
+        return;
+    }
+    let instructions = &program.instructions;
+
+    let mut states: Vec<Option<State>> = vec![None; instructions.len()];
+    states[0] = Some(State {
+        written: vec![false; variables.len()],
+        a: None,
+    });
+    let mut worklist = VecDeque::from([0]);
+    while let Some(id) = worklist.pop_front() {
+        let Some(mut state) = states[id].clone() else {
+            continue;
+        };
+        let jump_target = state.a;
+        let mut successors = Vec::new();
+        match &instructions[id] {
+            HackInstruction::A(AInstruction::Number(value)) => {
+                state.a = Some(*value);
+                successors.push(id + 1);
+            }
+            HackInstruction::C(ins) => {
+                if ins.writes_memory() {
+                    if let Some(&index) = state.a.and_then(|a| variables.get(&a)) {
+                        state.written[index] = true;
+                    }
+                }
+                if ins.writes_a() {
+                    state.a = None;
+                }
+                // computed jumps, like returns from subroutines, are not followed. Joining
+                // the state of every caller would report most variables of translated code
+                if let (true, Some(target)) = (ins.jumps(), jump_target) {
+                    successors.push(target as usize);
+                }
+                if !ins.always_jumps() {
+                    successors.push(id + 1);
+                }
+            }
+            // execution doesn't continue into data
+            _ => {}
+        }
+        for successor in successors {
+            let Some(successor_state) = states.get_mut(successor) else {
+                continue;
+            };
+            let changed = match successor_state {
+                Some(existing) => existing.join(&state),
+                None => {
+                    *successor_state = Some(state.clone());
+                    true
+                }
+            };
+            if changed {
+                worklist.push_back(successor);
+            }
+        }
+    }
+
+    let mut reported = vec![false; variables.len()];
+    for (id, ins) in instructions.iter().enumerate() {
+        let (HackInstruction::C(ins), Some(state)) = (ins, &states[id]) else {
+            continue;
+        };
+        if !ins.reads_memory() {
+            continue;
+        }
+        let Some(&index) = state.a.and_then(|a| variables.get(&a)) else {
+            continue;
+        };
+        if state.written[index] || reported[index] {
+            continue;
+        }
+        reported[index] = true;
+        warnings.push(
+            Warning::new(
+                Lint::UninitialisedRead,
+                format!(
+                    "Variable {} may be read before it is written",
+                    allocated[index].name
+                ),
+                program.locations[id],
+            )
+            .with_help(
+                "RAM is not cleared on reset, write the variable before reading it".to_string(),
+            ),
+        );
+    }
+}
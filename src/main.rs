@@ -1,18 +1,83 @@
-use rusty_hack_asm::run;
+use rusty_hack_asm::run_with_options;
+use rusty_hack_asm::Endianness;
 use rusty_hack_asm::Error;
+use rusty_hack_asm::LineEnding;
+use rusty_hack_asm::LintLevel;
+use rusty_hack_asm::Options;
+use rusty_hack_asm::OutputFormat;
+use rusty_hack_asm::Radix;
 use rusty_hack_asm::Result;
+use rusty_hack_asm::SymbolOrder;
+
+const USAGE: &str = "Usage: rusty_hack_asm [--strict] [--extended-isa] [-D NAME[=VALUE]]... \
+                     [--var-base ADDRESS] [--var-limit ADDRESS] \
+                     [--allow LINT] [--warn LINT] [--deny LINT] \
+                     [--format FORMAT] [--endian big|little] \
+                     [--line-ending lf|crlf|native] [--no-trailing-newline] \
+                     [--depth WORDS] [--radix bin|dec|hex] [--mem-addresses] [--mem-comments] \
+                     [--listing] [--symbols] [--symbols-json] \
+                     [--symbol-order address|name] [--source-map] <file>";
 
 fn main() -> Result<()> {
-    let path = file_path()?;
-    run(path)
+    let (path, mut options) = parse_args(Options::new())?;
+    // flags override the project configuration, so they are applied again on top of it
+    if let Some(config) = Options::config_path(&path) {
+        let mut base = Options::new();
+        base.load_config(&config)?;
+        options = parse_args(base)?.1;
+    }
+    run_with_options(path, &options)
 }
 
-fn file_path() -> Result<String> {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() != 2 {
-        return Err(Error::from(
-            "No file specified. Usage: rusty_rusty_hack_asm <file>",
-        ));
+fn parse_args(mut options: Options) -> Result<(String, Options)> {
+    let mut path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| Error::from(format!("Expected a value after '{}'. {}", flag, USAGE)))
+        };
+        match flag {
+            "--strict" => options.strict = true,
+            "--extended-isa" => options.extended_isa = true,
+            "--var-base" => options.var_base = rusty_hack_asm::parse_number(&value()?)?,
+            "--var-limit" => options.var_limit = rusty_hack_asm::parse_number(&value()?)?,
+            "--format" => options.format = OutputFormat::from_name(&value()?)?,
+            "--endian" => options.endianness = Endianness::from_name(&value()?)?,
+            "--line-ending" => options.line_ending = LineEnding::from_name(&value()?)?,
+            "--no-trailing-newline" => options.trailing_newline = false,
+            "--depth" => options.depth = Some(rusty_hack_asm::parse_number(&value()?)? as usize),
+            "--radix" => options.radix = Radix::from_name(&value()?)?,
+            "--mem-addresses" => options.mem_addresses = true,
+            "--mem-comments" => options.mem_comments = true,
+            "--listing" => options.listing = true,
+            "--symbols" => options.symbols = true,
+            "--symbols-json" => options.symbols_json = true,
+            "--source-map" => options.source_map = true,
+            "--symbol-order" => options.symbol_order = SymbolOrder::from_name(&value()?)?,
+            "--allow" => options.set_lint_level(&value()?, LintLevel::Allow)?,
+            "--warn" => options.set_lint_level(&value()?, LintLevel::Warn)?,
+            "--deny" => options.set_lint_level(&value()?, LintLevel::Deny)?,
+            "-D" => options.add_define(&value()?)?,
+            _ if flag.starts_with("-D") => options.add_define(&arg[2..])?,
+            _ if flag.starts_with('-') => {
+                return Err(Error::from(format!("Unknown option '{}'. {}", flag, USAGE)))
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => {
+                return Err(Error::from(format!(
+                    "Unexpected argument '{}'. {}",
+                    arg, USAGE
+                )))
+            }
+        }
     }
-    Ok(args.into_iter().nth(1).unwrap())
+    let path = path.ok_or_else(|| Error::from(format!("No file specified. {}", USAGE)))?;
+    Ok((path, options))
 }
@@ -0,0 +1,367 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::{self, Entry, Value},
+    lints::{Lint, LintLevel},
+    suggestions, Error, Result,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    // one word per line as '0' and '1' characters, what the nand2tetris tools load
+    Hack,
+    // raw ROM image, two bytes per word
+    Bin,
+    IntelHex,
+    // ROM image for Logisim-evolution
+    Logisim,
+    // memory initialisation files for Verilog
+    ReadMemB,
+    ReadMemH,
+    // memory initialisation files for Intel/Altera and Xilinx block RAM
+    Mif,
+    Coe,
+    // source arrays with the symbol table as constants, for embedding in emulators
+    C,
+    Rust,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "hack" => Ok(OutputFormat::Hack),
+            "bin" => Ok(OutputFormat::Bin),
+            "ihex" => Ok(OutputFormat::IntelHex),
+            "logisim" => Ok(OutputFormat::Logisim),
+            "readmemb" => Ok(OutputFormat::ReadMemB),
+            "readmemh" => Ok(OutputFormat::ReadMemH),
+            "mif" => Ok(OutputFormat::Mif),
+            "coe" => Ok(OutputFormat::Coe),
+            "c" => Ok(OutputFormat::C),
+            "rust" => Ok(OutputFormat::Rust),
+            _ => Err(Error::from(format!(
+                "Unknown output format '{}', expected hack, bin, ihex, logisim, readmemb, readmemh, \
+                 mif, coe, c or rust",
+                name
+            ))),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Hack => "hack",
+            OutputFormat::Bin => "bin",
+            OutputFormat::IntelHex => "hex",
+            OutputFormat::Logisim => "rom",
+            OutputFormat::ReadMemB | OutputFormat::ReadMemH => "mem",
+            OutputFormat::Mif => "mif",
+            OutputFormat::Coe => "coe",
+            OutputFormat::C => "c",
+            OutputFormat::Rust => "rs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Big,
+    Little,
+}
+
+impl Endianness {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "big" => Ok(Endianness::Big),
+            "little" => Ok(Endianness::Little),
+            _ => Err(Error::from(format!(
+                "Unknown byte order '{}', expected big or little",
+                name
+            ))),
+        }
+    }
+}
+
+// how words are written in memory initialisation files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Binary,
+    Decimal,
+    Hex,
+}
+
+impl Radix {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "bin" => Ok(Radix::Binary),
+            "dec" => Ok(Radix::Decimal),
+            "hex" => Ok(Radix::Hex),
+            _ => Err(Error::from(format!(
+                "Unknown radix '{}', expected bin, dec or hex",
+                name
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolOrder {
+    Address,
+    Name,
+}
+
+impl SymbolOrder {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "address" => Ok(SymbolOrder::Address),
+            "name" => Ok(SymbolOrder::Name),
+            _ => Err(Error::from(format!(
+                "Unknown symbol order '{}', expected address or name",
+                name
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    // CRLF on Windows, LF everywhere else
+    Native,
+}
+
+impl LineEnding {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "lf" => Ok(LineEnding::Lf),
+            "crlf" => Ok(LineEnding::CrLf),
+            "native" => Ok(LineEnding::Native),
+            _ => Err(Error::from(format!(
+                "Unknown line ending '{}', expected lf, crlf or native",
+                name
+            ))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Native if cfg!(windows) => "\r\n",
+            LineEnding::Native => "\n",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub defines: Vec<(String, u16)>,
+    // rejects extensions to the nand2tetris language, like pseudo-instructions
+    pub strict: bool,
+    // enables the shift instructions, needs the `extended-isa` cargo feature
+    pub extended_isa: bool,
+    // RAM range used for automatically allocated variables
+    pub var_base: u16,
+    pub var_limit: u16,
+    // lint names or `warnings` with their level, later entries take precedence
+    pub lint_levels: Vec<(String, LintLevel)>,
+    pub format: OutputFormat,
+    // byte order of words in binary formats
+    pub endianness: Endianness,
+    // line ending of text formats, and whether the last line has one too
+    pub line_ending: LineEnding,
+    pub trailing_newline: bool,
+    // memory formats are padded with zeros up to this many words
+    pub depth: Option<usize>,
+    pub radix: Radix,
+    // `@address` markers and source comments in Verilog memory files
+    pub mem_addresses: bool,
+    pub mem_comments: bool,
+    // also write a `.lst` listing of the program
+    pub listing: bool,
+    // also write the symbol table as `.sym` text and `.sym.json`
+    pub symbols: bool,
+    pub symbols_json: bool,
+    pub symbol_order: SymbolOrder,
+    // also write a `.map.json` source map from ROM addresses to source spans
+    pub source_map: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            defines: Vec::new(),
+            strict: false,
+            extended_isa: false,
+            var_base: 16,
+            var_limit: 16383,
+            lint_levels: Vec::new(),
+            format: OutputFormat::Hack,
+            endianness: Endianness::Big,
+            line_ending: LineEnding::CrLf,
+            trailing_newline: true,
+            depth: None,
+            radix: Radix::Hex,
+            mem_addresses: false,
+            mem_comments: false,
+            listing: false,
+            symbols: false,
+            symbols_json: false,
+            symbol_order: SymbolOrder::Address,
+            source_map: false,
+        }
+    }
+}
+
+impl Options {
+    pub const CONFIG_FILE: &'static str = "hackasm.toml";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the project configuration lives next to the source file
+    pub fn config_path(source: &str) -> Option<PathBuf> {
+        let path = Path::new(source).parent()?.join(Self::CONFIG_FILE);
+        path.is_file().then_some(path)
+    }
+
+    pub fn load_config(&mut self, path: &Path) -> Result<()> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| Error::from(format!("Cannot read '{}': {}", path.display(), err)))?;
+        let file = path.display().to_string();
+        for entry in config::parse(&text, &file)? {
+            self.apply_config_entry(&entry)
+                .map_err(|err| config::error(&file, entry.line, &err.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn apply_config_entry(&mut self, entry: &Entry) -> Result<()> {
+        let expected = |what: &str| Error::from(format!("Expected {} for '{}'", what, entry.key));
+        match (entry.table.as_deref(), entry.key.as_str(), &entry.value) {
+            (None, "strict", &Value::Bool(strict)) => self.strict = strict,
+            (None, "extended-isa", &Value::Bool(extended_isa)) => self.extended_isa = extended_isa,
+            (None, "trailing-newline", &Value::Bool(trailing_newline)) => {
+                self.trailing_newline = trailing_newline
+            }
+            (None, "mem-addresses", &Value::Bool(mem_addresses)) => {
+                self.mem_addresses = mem_addresses
+            }
+            (None, "mem-comments", &Value::Bool(mem_comments)) => self.mem_comments = mem_comments,
+            (None, "listing", &Value::Bool(listing)) => self.listing = listing,
+            (None, "symbols", &Value::Bool(symbols)) => self.symbols = symbols,
+            (None, "symbols-json", &Value::Bool(symbols_json)) => self.symbols_json = symbols_json,
+            (None, "source-map", &Value::Bool(source_map)) => self.source_map = source_map,
+            (
+                None,
+                "strict" | "extended-isa" | "trailing-newline" | "mem-addresses" | "mem-comments"
+                | "listing" | "symbols" | "symbols-json" | "source-map",
+                _,
+            ) => return Err(expected("true or false")),
+            (None, "depth", &Value::Integer(depth)) => {
+                let depth = usize::try_from(depth).map_err(|_| expected("a word count"))?;
+                self.depth = Some(depth)
+            }
+            (None, "depth", _) => return Err(expected("a word count")),
+            (None, "var-base", &Value::Integer(address)) => {
+                self.var_base = parse_number(&address.to_string())?
+            }
+            (None, "var-limit", &Value::Integer(address)) => {
+                self.var_limit = parse_number(&address.to_string())?
+            }
+            (None, "var-base" | "var-limit", _) => return Err(expected("an address")),
+            (None, "format", Value::Str(format)) => self.format = OutputFormat::from_name(format)?,
+            (None, "endian", Value::Str(endian)) => {
+                self.endianness = Endianness::from_name(endian)?
+            }
+            (None, "line-ending", Value::Str(line_ending)) => {
+                self.line_ending = LineEnding::from_name(line_ending)?
+            }
+            (None, "radix", Value::Str(radix)) => self.radix = Radix::from_name(radix)?,
+            (None, "symbol-order", Value::Str(order)) => {
+                self.symbol_order = SymbolOrder::from_name(order)?
+            }
+            (None, "format" | "endian" | "line-ending" | "radix" | "symbol-order", _) => {
+                return Err(expected("a string"))
+            }
+            (None, key, _) => return Err(Error::from(format!("Unknown option '{}'", key))),
+            (Some("lints"), name, Value::Str(level)) => {
+                let level = LintLevel::from_name(level).ok_or_else(|| {
+                    Error::from(format!(
+                        "Unknown lint level '{}', expected \"allow\", \"warn\" or \"deny\"",
+                        level
+                    ))
+                })?;
+                self.set_lint_level(name, level)?
+            }
+            (Some("lints"), _, _) => return Err(expected("a lint level string")),
+            (Some("defines"), name, &Value::Integer(value)) => {
+                self.add_define(&format!("{}={}", name, value))?
+            }
+            (Some("defines"), _, _) => return Err(expected("a number")),
+            (Some(table), _, _) => return Err(Error::from(format!("Unknown table '[{}]'", table))),
+        }
+        Ok(())
+    }
+
+    // accepts `NAME` (defined as 1) or `NAME=VALUE`, as passed to `-D`
+    pub fn add_define(&mut self, spec: &str) -> Result<()> {
+        let (name, value) = match spec.split_once('=') {
+            Some((name, value)) => (name, parse_number(value)?),
+            None => (spec, 1),
+        };
+        if !is_valid_symbol(name) {
+            return Err(Error::from(format!(
+                "Invalid symbol name '{}' in define",
+                name
+            )));
+        }
+        self.defines.retain(|(defined, _)| defined != name);
+        self.defines.push((name.to_string(), value));
+        Ok(())
+    }
+
+    pub fn set_lint_level(&mut self, name: &str, level: LintLevel) -> Result<()> {
+        if name != Lint::GROUP_ALL && Lint::from_name(name).is_none() {
+            let names = Lint::ALL.map(|lint| lint.name());
+            let mut message = format!("Unknown lint '{}'", name);
+            if let Some(similar) = suggestions::closest(name, names) {
+                message.push_str(&format!(", did you mean '{}'?", similar));
+            }
+            return Err(Error::from(message));
+        }
+        self.lint_levels.push((name.to_string(), level));
+        Ok(())
+    }
+
+    pub fn lint_level(&self, lint: Lint) -> LintLevel {
+        self.lint_levels
+            .iter()
+            .rev()
+            .find(|(name, _)| name == lint.name() || name == Lint::GROUP_ALL)
+            .map_or(LintLevel::Warn, |&(_, level)| level)
+    }
+}
+
+pub fn parse_number(text: &str) -> Result<u16> {
+    let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    parsed.map_err(|_| {
+        Error::from(format!(
+            "Invalid value '{}', expected a number from 0 to 65535",
+            text
+        ))
+    })
+}
+
+fn is_valid_symbol(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$'))
+}
@@ -1,99 +1,136 @@
+use std::borrow::Cow;
 use std::cmp;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::ast::{AInstruction, CInstruction, Comp, HackInstruction};
+use crate::ast::{
+    AInstruction, CInstruction, Comp, HackInstruction, Label, Location, Program, SymbolKind,
+    SymbolRef, Variable,
+};
+use crate::expression::Expr;
+use crate::lints::{Lint, Warning};
 use crate::scanner::token::TokenKind;
+use crate::suggestions;
 use crate::{
     error_formatting::ErrorFormatter,
     scanner::token::{self, Token},
-    Error, Result,
+    Error, Options, Result,
 };
 
+struct Assertion<'a> {
+    directive: &'a Token<'a>,
+    expr_start: &'a Token<'a>,
+    expr_end: &'a Token<'a>,
+    expr: Expr<'a>,
+    message: Option<&'a str>,
+    scope: Option<&'a str>,
+}
+
 pub struct Parser<'a> {
     source: &'a [&'a str],
     tokens: &'a [Token<'a>],
+    options: &'a Options,
     curr: usize,
     next_ident_id: u16,
     errors: Vec<Error>,
     instructions: Vec<HackInstruction<'a>>,
-    var_a_ins_indices: Vec<usize>,
-    ident_map: HashMap<&'a str, u16>,
+    locations: Vec<Location>,
+    expanded: Vec<bool>,
+    var_a_ins_indices: Vec<(usize, &'a Token<'a>)>,
+    ident_map: HashMap<Cow<'a, str>, u16>,
+    label_scope: Option<&'a str>,
+    numeric_labels: HashMap<u16, u16>,
+    forward_label_refs: Vec<(usize, &'a Token<'a>)>,
+    local_label_refs: Vec<(usize, &'a Token<'a>)>,
+    placed_vars: HashMap<u16, &'a str>,
+    labels: Vec<Label<'a>>,
+    label_names: HashSet<Cow<'a, str>>,
+    references: Vec<SymbolRef<'a>>,
+    variables: Vec<Variable<'a>>,
+    assertions: Vec<Assertion<'a>>,
+    warnings: Vec<Warning>,
 }
 
 impl<'a> Parser<'a> {
-    const MAX_VAR_ADDRESS: u16 = 16383;
-    pub fn new(tokens: &'a [Token<'a>], source: &'a [&'a str]) -> Self {
+    const MAX_ADDRESS: u16 = 32767;
+    pub const ROM_SIZE: usize = 32768;
+    pub const PREDEFINED_IDENTS: [&'static str; 23] = [
+        "SP", "LCL", "ARG", "THIS", "THAT", "R0", "R1", "R2", "R3", "R4", "R5", "R6", "R7", "R8",
+        "R9", "R10", "R11", "R12", "R13", "R14", "R15", "SCREEN", "KBD",
+    ];
+    const JUMP_MNEMONICS: [&'static str; 7] = ["JGT", "JEQ", "JGE", "JLT", "JNE", "JLE", "JMP"];
+    pub fn new(tokens: &'a [Token<'a>], source: &'a [&'a str], options: &'a Options) -> Self {
         let identifier_map = Self::get_default_ident_map();
 
         Parser {
             tokens,
             source,
+            options,
             curr: 0,
-            next_ident_id: 16,
+            next_ident_id: options.var_base,
             errors: Vec::new(),
             instructions: Vec::new(),
+            locations: Vec::new(),
+            expanded: Vec::new(),
             ident_map: identifier_map,
             var_a_ins_indices: Vec::new(),
+            label_scope: None,
+            numeric_labels: HashMap::new(),
+            forward_label_refs: Vec::new(),
+            local_label_refs: Vec::new(),
+            placed_vars: HashMap::new(),
+            labels: Vec::new(),
+            label_names: HashSet::new(),
+            references: Vec::new(),
+            variables: Vec::new(),
+            assertions: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
     fn is_predefined_ident(&self, ident: &str) -> bool {
-        matches!(
-            ident,
-            "SP" | "LCL"
-                | "ARG"
-                | "THIS"
-                | "THAT"
-                | "R0"
-                | "R1"
-                | "R2"
-                | "R3"
-                | "R4"
-                | "R5"
-                | "R6"
-                | "R7"
-                | "R8"
-                | "R9"
-                | "R10"
-                | "R11"
-                | "R12"
-                | "R13"
-                | "R14"
-                | "R15"
-                | "SCREEN"
-                | "KBD"
-        )
+        Self::PREDEFINED_IDENTS.contains(&ident)
     }
 
-    fn get_default_ident_map() -> HashMap<&'a str, u16> {
+    pub fn get_default_ident_map() -> HashMap<Cow<'a, str>, u16> {
         let mut map = HashMap::with_capacity(23);
-        map.insert("SP", 0);
-        map.insert("LCL", 1);
-        map.insert("ARG", 2);
-        map.insert("THIS", 3);
-        map.insert("THAT", 4);
-        map.insert("R0", 0);
-        map.insert("R1", 1);
-        map.insert("R2", 2);
-        map.insert("R3", 3);
-        map.insert("R4", 4);
-        map.insert("R5", 5);
-        map.insert("R6", 6);
-        map.insert("R7", 7);
-        map.insert("R8", 8);
-        map.insert("R9", 9);
-        map.insert("R10", 10);
-        map.insert("R11", 11);
-        map.insert("R12", 12);
-        map.insert("R13", 13);
-        map.insert("R14", 14);
-        map.insert("R15", 15);
-        map.insert("SCREEN", 16384);
-        map.insert("KBD", 24576);
+        map.insert(Cow::Borrowed("SP"), 0);
+        map.insert(Cow::Borrowed("LCL"), 1);
+        map.insert(Cow::Borrowed("ARG"), 2);
+        map.insert(Cow::Borrowed("THIS"), 3);
+        map.insert(Cow::Borrowed("THAT"), 4);
+        map.insert(Cow::Borrowed("R0"), 0);
+        map.insert(Cow::Borrowed("R1"), 1);
+        map.insert(Cow::Borrowed("R2"), 2);
+        map.insert(Cow::Borrowed("R3"), 3);
+        map.insert(Cow::Borrowed("R4"), 4);
+        map.insert(Cow::Borrowed("R5"), 5);
+        map.insert(Cow::Borrowed("R6"), 6);
+        map.insert(Cow::Borrowed("R7"), 7);
+        map.insert(Cow::Borrowed("R8"), 8);
+        map.insert(Cow::Borrowed("R9"), 9);
+        map.insert(Cow::Borrowed("R10"), 10);
+        map.insert(Cow::Borrowed("R11"), 11);
+        map.insert(Cow::Borrowed("R12"), 12);
+        map.insert(Cow::Borrowed("R13"), 13);
+        map.insert(Cow::Borrowed("R14"), 14);
+        map.insert(Cow::Borrowed("R15"), 15);
+        map.insert(Cow::Borrowed("SCREEN"), 16384);
+        map.insert(Cow::Borrowed("KBD"), 24576);
         map
     }
 
-    pub fn run(mut self) -> Result<Vec<HackInstruction<'a>>> {
+    pub fn run(mut self) -> Result<Program<'a>> {
+        if self.options.var_base > self.options.var_limit
+            || self.options.var_limit > Self::MAX_ADDRESS
+        {
+            return Err(Error::from(format!(
+                "Invalid variable range {}..{}, addresses range from 0 to {}",
+                self.options.var_base,
+                self.options.var_limit,
+                Self::MAX_ADDRESS
+            )));
+        }
+        self.add_defines()?;
         if !self.parse() {
             self.print_errors();
             return Err(Error::from(format!(
@@ -102,33 +139,248 @@ impl<'a> Parser<'a> {
             )));
         }
         self.denote_variables()?;
-        Ok(self.instructions)
+        self.check_assertions();
+        if !self.errors.is_empty() {
+            self.print_errors();
+            return Err(Error::from(format!(
+                "Encountered {}, errors, aborting compilation.",
+                self.errors.len()
+            )));
+        }
+        Ok(Program {
+            instructions: self.instructions,
+            locations: self.locations,
+            expanded: self.expanded,
+            labels: self.labels,
+            references: self.references,
+            variables: self.variables,
+            warnings: self.warnings,
+        })
+    }
+
+    fn add_defines(&mut self) -> Result<()> {
+        for (name, value) in &self.options.defines {
+            if self.is_predefined_ident(name) {
+                return Err(Error::from(format!(
+                    "Identifier {} is predefined and cannot be redefined",
+                    name
+                )));
+            }
+            self.ident_map.insert(Cow::Borrowed(name), *value);
+        }
+        Ok(())
     }
 
     fn denote_variables(&mut self) -> Result<()> {
-        for &id in self.var_a_ins_indices.iter() {
-            if let HackInstruction::A(AInstruction::Identifier(ident)) = self.instructions[id] {
-                if !self.ident_map.contains_key(ident) && self.next_ident_id > Self::MAX_VAR_ADDRESS
+        let mut uses: HashMap<Cow<'a, str>, usize> = HashMap::new();
+        let mut allocated = Vec::new();
+        for &(id, token) in self.var_a_ins_indices.iter() {
+            if let Some(AInstruction::Identifier(ident)) = self.instructions[id].address_mut() {
+                let ident = ident.clone();
+                *uses.entry(ident.clone()).or_default() += 1;
+                let val = match self.ident_map.get(&ident) {
+                    Some(&val) => val,
+                    None => {
+                        if self.next_ident_id > self.options.var_limit {
+                            return Err(Error::from(format!(
+                                "Introduced too many variables, max variable count is {} (or max as address is {})",
+                                self.options.var_limit - self.options.var_base + 1,
+                                self.options.var_limit,
+                            )));
+                        }
+                        let index = self.next_ident_id;
+                        self.next_ident_id += 1;
+                        if let Some(placed) = self.placed_vars.get(&index) {
+                            self.errors.push(ErrorFormatter::err_from_token(
+                                &format!(
+                                    "Variable {} is allocated to address {}, which is already used by '.var {}'",
+                                    ident, index, placed
+                                ),
+                                self.source,
+                                token,
+                            ));
+                        }
+                        allocated.push((ident.clone(), token));
+                        self.variables.push(Variable {
+                            name: ident.clone(),
+                            address: index,
+                            location: Self::token_location(token),
+                            allocated: true,
+                        });
+                        self.ident_map.insert(ident.clone(), index);
+                        index
+                    }
+                };
+                // only labels and variables are known to fit, constants may use all 16 bits
+                // for data words but not as an address
+                if val > Self::MAX_ADDRESS && matches!(self.instructions[id], HackInstruction::A(_))
                 {
-                    return Err(Error::from(format!(
-                        "Introduced too many variables, max variable count is {} (or max as address is {})",
-                        Self::MAX_VAR_ADDRESS - 15,
-                        Self::MAX_VAR_ADDRESS,
-                    )));
+                    self.errors.push(ErrorFormatter::err_from_token(
+                        &format!(
+                            "Constant {} is {}, which is out of range as an address. Address ranges from 0 to {}",
+                            ident,
+                            val,
+                            Self::MAX_ADDRESS
+                        ),
+                        self.source,
+                        token,
+                    ));
                 }
-                let val = self.ident_map.entry(ident).or_insert_with(|| {
-                    let index = self.next_ident_id;
-                    self.next_ident_id += 1;
-                    index
+                self.references.push(SymbolRef {
+                    kind: self.symbol_kind(&ident),
+                    name: ident,
+                    instruction: Some(id),
+                    location: Self::token_location(token),
                 });
-                self.instructions[id] = HackInstruction::A(AInstruction::Number(*val));
+                if let Some(address) = self.instructions[id].address_mut() {
+                    *address = AInstruction::Number(val);
+                }
             } else {
                 unimplemented!()
             }
         }
+        for (variable, token) in allocated {
+            self.warn_similar_name(&variable, token, uses[&variable]);
+        }
         Ok(())
     }
 
+    // a new variable is often a misspelled symbol, as every unknown name allocates one.
+    // Short names like `i` and `j` are always one edit apart, so they are not compared
+    fn warn_similar_name(&mut self, variable: &str, token: &Token, uses: usize) {
+        let location = Self::token_location(token);
+        let predefined = Self::PREDEFINED_IDENTS
+            .into_iter()
+            .find(|ident| ident.eq_ignore_ascii_case(variable));
+        let warning = if let Some(predefined) = predefined {
+            Warning::new(
+                Lint::SimilarName,
+                format!(
+                    "Variable {} is not the predefined symbol {}, symbols are case-sensitive",
+                    variable, predefined
+                ),
+                location,
+            )
+            .with_help(format!("did you mean `{}`?", predefined))
+        } else if uses == 1 && variable.len() > 2 {
+            let Some(similar) = suggestions::closest(variable, self.ident_map.keys().map(|k| &**k))
+            else {
+                return;
+            };
+            Warning::new(
+                Lint::SimilarName,
+                format!("Variable {} is only used once", variable),
+                location,
+            )
+            .with_help(format!("did you mean `{}`?", similar))
+        } else {
+            return;
+        };
+        self.warnings.push(warning);
+    }
+
+    fn symbol_kind(&self, name: &str) -> SymbolKind {
+        if self.label_names.contains(name) {
+            SymbolKind::Label
+        } else if self.is_predefined_ident(name) {
+            SymbolKind::Predefined
+        } else if self
+            .options
+            .defines
+            .iter()
+            .any(|(define, _)| define == name)
+        {
+            SymbolKind::Constant
+        } else {
+            SymbolKind::Variable
+        }
+    }
+
+    fn check_assertions(&mut self) {
+        for assertion in std::mem::take(&mut self.assertions) {
+            let mut symbols = Vec::new();
+            assertion.expr.symbols(&mut symbols);
+            for symbol in &symbols {
+                if let token::TokenKind::Identifier(name) = symbol.kind {
+                    let name = match assertion.scope {
+                        Some(scope) if name.starts_with('.') => {
+                            Cow::Owned(format!("{}{}", scope, name))
+                        }
+                        _ => Cow::Borrowed(name),
+                    };
+                    if self.ident_map.contains_key(&name) {
+                        self.references.push(SymbolRef {
+                            kind: self.symbol_kind(&name),
+                            name,
+                            instruction: None,
+                            location: Self::token_location(symbol),
+                        });
+                    }
+                }
+            }
+
+            let ident_map = &self.ident_map;
+            let lookup = |name: &str| {
+                let value = match assertion.scope {
+                    Some(scope) if name.starts_with('.') => {
+                        ident_map.get(format!("{}{}", scope, name).as_str())
+                    }
+                    _ => ident_map.get(name),
+                };
+                value.map(|&value| value as i64)
+            };
+            let value = match assertion.expr.eval(&lookup) {
+                Ok(value) => value,
+                Err(err) => {
+                    self.errors.push(ErrorFormatter::err_from_token(
+                        &err.message,
+                        self.source,
+                        &err.token,
+                    ));
+                    continue;
+                }
+            };
+            if value != 0 {
+                continue;
+            }
+
+            let mut note = match &assertion.expr {
+                Expr::Binary(op, left, right) => format!(
+                    "{} {} {} is false",
+                    left.eval(&lookup).unwrap_or_default(),
+                    op.symbol(),
+                    right.eval(&lookup).unwrap_or_default()
+                ),
+                _ => format!("expression evaluated to {}", value),
+            };
+            let mut names: Vec<&str> = Vec::new();
+            for symbol in symbols {
+                if let token::TokenKind::Identifier(name) = symbol.kind {
+                    if !names.contains(&name) {
+                        note.push_str(if names.is_empty() { ", where " } else { ", " });
+                        note.push_str(&format!("{} = {}", name, lookup(name).unwrap_or_default()));
+                        names.push(name);
+                    }
+                }
+            }
+
+            let line = self.source[assertion.directive.line];
+            let expr_end = assertion.expr_end.start + assertion.expr_end.len();
+            let message = assertion
+                .message
+                .unwrap_or(&line[assertion.expr_start.start..expr_end]);
+            let error = ErrorFormatter::gen_err(
+                &format!("Assertion failed: {}", message),
+                self.source,
+                assertion.directive.start,
+                expr_end - assertion.directive.start,
+                assertion.directive.line,
+            );
+            self.errors
+                .push(ErrorFormatter::add_note(error, "note", &note));
+        }
+    }
+
     fn print_errors(&self) {
         for error in &self.errors {
             eprintln!("{}", error);
@@ -140,12 +392,22 @@ impl<'a> Parser<'a> {
         while !self.is_at_end() {
             self.statement();
         }
+        self.check_forward_label_refs();
+        self.check_local_label_refs();
+        self.check_rom_size();
         self.errors.is_empty()
     }
 
     fn statement(&mut self) {
+        let first_id = self.curr;
+        let pseudo = self.is_pseudo_instruction();
         let success = match self.peek().kind {
             token::TokenKind::LeftParen => self.label_declaration(),
+            token::TokenKind::Number(_, _) if self.check_next(token::TokenKind::Colon) => {
+                self.numeric_label_declaration()
+            }
+            token::TokenKind::Directive(_) => self.directive(),
+            _ if self.is_pseudo_instruction() => self.pseudo_instruction(),
             _ => {
                 if let Some(ins) = self.instruction() {
                     self.instructions.push(ins);
@@ -155,6 +417,9 @@ impl<'a> Parser<'a> {
                 }
             }
         };
+        let location = self.location_since(first_id);
+        self.locations.resize(self.instructions.len(), location);
+        self.expanded.resize(self.instructions.len(), pseudo);
 
         if !success
             || !self.consume_line_end(
@@ -165,22 +430,63 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn add_label_ident(&mut self, ident: &'a str) {
-        if self.is_predefined_ident(ident) {
+    // span from the token at `first_id` up to the last consumed token
+    fn location_since(&self, first_id: usize) -> Location {
+        let first = &self.tokens[first_id];
+        let last = &self.tokens[cmp::max(self.curr, first_id + 1) - 1];
+        Location {
+            line: first.line,
+            start: first.start,
+            len: (last.start + last.len()).saturating_sub(first.start),
+        }
+    }
+
+    fn token_location(token: &Token) -> Location {
+        Location {
+            line: token.line,
+            start: token.start,
+            len: token.len(),
+        }
+    }
+
+    fn add_label_ident(&mut self, ident: Cow<'a, str>, location: Location) {
+        if self.is_predefined_ident(&ident) {
             self.raise_error_prev(&format!(
                 "Identifier {} is predefined and cannot be redefined",
                 ident,
             ));
-        } else if self.ident_map.contains_key(ident) {
+        } else if self.options.defines.iter().any(|(name, _)| *name == ident) {
+            self.raise_error_prev(&format!(
+                "Cannot declare label {}, it is already defined as a constant",
+                ident,
+            ));
+        } else if self.ident_map.contains_key(&ident) {
             self.raise_error_prev(&format!("Cannot declare label {} more than once", ident,));
-        } else {
-            self.ident_map.insert(ident, self.instructions.len() as u16);
+        } else if self.check_label_address(&ident) {
+            let address = self.instructions.len() as u16;
+            self.labels.push(Label {
+                name: Some(ident.clone()),
+                address,
+                location,
+            });
+            self.label_names.insert(ident.clone());
+            self.ident_map.insert(ident, address);
         }
     }
 
     fn label_declaration(&mut self) -> bool {
+        let first_id = self.curr;
         self.advance();
         if let Some(label) = self.consume_identifier("Expected label name after '('") {
+            let label = if label.starts_with('.') {
+                match self.scoped_label(label) {
+                    Some(label) => label,
+                    None => return false,
+                }
+            } else {
+                self.label_scope = Some(label);
+                Cow::Borrowed(label)
+            };
             if self
                 .consume(
                     "Expected ')' after label name",
@@ -190,13 +496,115 @@ impl<'a> Parser<'a> {
             {
                 return false;
             }
-            self.add_label_ident(label);
+            self.add_label_ident(label, self.location_since(first_id));
             true
         } else {
             false
         }
     }
 
+    // local labels like `.loop` belong to the closest global label declared before them
+    fn scoped_label(&mut self, local: &'a str) -> Option<Cow<'a, str>> {
+        if let Some(scope) = self.label_scope {
+            Some(Cow::Owned(format!("{}{}", scope, local)))
+        } else {
+            self.raise_error_prev(&format!(
+                "Local label {} has no enclosing global label",
+                local
+            ));
+            None
+        }
+    }
+
+    fn numeric_label_declaration(&mut self) -> bool {
+        let label = match self.peek().kind {
+            token::TokenKind::Number(label, _) => label,
+            _ => unreachable!(),
+        };
+        let first_id = self.curr;
+        self.advance(); // skip label number
+        self.advance(); // skip ':'
+        if !self.check_label_address(&format!("{}:", label)) {
+            return false;
+        }
+        let address = self.instructions.len() as u16;
+        self.labels.push(Label {
+            name: None,
+            address,
+            location: self.location_since(first_id),
+        });
+        self.numeric_labels.insert(label, address);
+
+        let refs = std::mem::take(&mut self.forward_label_refs);
+        for (id, token) in refs {
+            match token.kind {
+                token::TokenKind::ForwardLabel(n, _) if n == label => {
+                    if let Some(ins) = self.instructions[id].address_mut() {
+                        *ins = AInstruction::Number(address);
+                    }
+                }
+                _ => self.forward_label_refs.push((id, token)),
+            }
+        }
+        true
+    }
+
+    fn check_rom_size(&mut self) {
+        if self.instructions.len() > Self::ROM_SIZE {
+            let location = self.locations[Self::ROM_SIZE];
+            self.errors.push(ErrorFormatter::gen_err(
+                &format!(
+                    "Program does not fit in ROM, it is {} words long but ROM holds {} words",
+                    self.instructions.len(),
+                    Self::ROM_SIZE
+                ),
+                self.source,
+                location.start,
+                location.len,
+                location.line,
+            ));
+        }
+    }
+
+    fn check_label_address(&mut self, label: &str) -> bool {
+        if self.instructions.len() > Self::MAX_ADDRESS as usize {
+            self.raise_error_prev(&format!(
+                "Label {} points at address {}, past the end of ROM",
+                label,
+                self.instructions.len()
+            ));
+            false
+        } else {
+            true
+        }
+    }
+
+    fn check_local_label_refs(&mut self) {
+        for (id, token) in std::mem::take(&mut self.local_label_refs) {
+            if let Some(AInstruction::Identifier(label)) = self.instructions[id].address_mut() {
+                if !self.ident_map.contains_key(label) {
+                    self.errors.push(ErrorFormatter::err_from_token(
+                        &format!("Local label {} is never declared", label),
+                        self.source,
+                        token,
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_forward_label_refs(&mut self) {
+        for (_, token) in std::mem::take(&mut self.forward_label_refs) {
+            if let token::TokenKind::ForwardLabel(label, _) = token.kind {
+                self.errors.push(ErrorFormatter::err_from_token(
+                    &format!("No numeric label {}: declared after this reference", label),
+                    self.source,
+                    token,
+                ));
+            }
+        }
+    }
+
     fn instruction(&mut self) -> Option<HackInstruction<'a>> {
         if self.check(token::TokenKind::At) {
             self.a_instruction()
@@ -207,24 +615,469 @@ impl<'a> Parser<'a> {
 
     fn a_instruction(&mut self) -> Option<HackInstruction<'a>> {
         self.advance(); //skip @ token
+        self.address("Expected identifier or number after '@'")
+    }
+
+    fn address(&mut self, msg: &str) -> Option<HackInstruction<'a>> {
+        if let token::TokenKind::Number(num, _) = self.peek().kind {
+            if num > Self::MAX_ADDRESS {
+                self.raise_error_peek(&format!(
+                    "Address out of range. Address ranges from 0 to {}",
+                    Self::MAX_ADDRESS
+                ));
+                return None;
+            }
+        }
+        Some(HackInstruction::A(self.value(msg)?))
+    }
+
+    fn value(&mut self, msg: &str) -> Option<AInstruction<'a>> {
         match self.peek().kind {
             token::TokenKind::Identifier(ident) => {
                 self.advance();
-                self.var_a_ins_indices.push(self.instructions.len());
-                Some(HackInstruction::A(AInstruction::Identifier(ident)))
+                let ident = if ident.starts_with('.') {
+                    let label = self.scoped_label(ident)?;
+                    self.local_label_refs
+                        .push((self.instructions.len(), self.previous()));
+                    label
+                } else {
+                    Cow::Borrowed(ident)
+                };
+                self.var_a_ins_indices
+                    .push((self.instructions.len(), self.previous()));
+                Some(AInstruction::Identifier(ident))
+            }
+            token::TokenKind::BackwardLabel(label, _) => {
+                self.advance();
+                self.numeric_label_ref();
+                if let Some(&address) = self.numeric_labels.get(&label) {
+                    Some(AInstruction::Number(address))
+                } else {
+                    self.raise_error_prev(&format!(
+                        "No numeric label {}: declared before this reference",
+                        label
+                    ));
+                    None
+                }
+            }
+            token::TokenKind::ForwardLabel(_, _) => {
+                self.advance();
+                self.numeric_label_ref();
+                self.forward_label_refs
+                    .push((self.instructions.len(), self.previous()));
+                // patched once the label is declared
+                Some(AInstruction::Number(0))
             }
             token::TokenKind::Number(num, _) => {
                 self.advance();
-                Some(HackInstruction::A(AInstruction::Number(num)))
+                Some(AInstruction::Number(num))
             }
             _ => {
-                self.raise_error_peek("Expected identifier or number after '@'");
+                self.raise_error_peek(msg);
                 None
-            } //todo raise an error
+            }
+        }
+    }
+
+    fn numeric_label_ref(&mut self) {
+        let token = self.previous();
+        let name = &self.source[token.line][token.start..token.start + token.len()];
+        self.references.push(SymbolRef {
+            name: Cow::Borrowed(name),
+            kind: SymbolKind::Label,
+            instruction: Some(self.instructions.len()),
+            location: Self::token_location(token),
+        });
+    }
+
+    fn directive(&mut self) -> bool {
+        if !self.allow_extension("Directives") {
+            return false;
+        }
+        let directive = self.peek().kind;
+        self.advance();
+        match directive {
+            token::TokenKind::Directive(".word") => loop {
+                let Some(word) = self.word() else {
+                    return false;
+                };
+                self.instructions.push(HackInstruction::Word(word));
+                if !self.check(token::TokenKind::Comma) {
+                    return true;
+                }
+                self.advance();
+            },
+            token::TokenKind::Directive(".fill") => {
+                let Some(count) = self.count("Expected word count after '.fill'") else {
+                    return false;
+                };
+                if count == 0 {
+                    self.raise_error_prev("'.fill' needs a word count of at least 1");
+                    return false;
+                }
+                if self
+                    .consume("Expected ',' after word count", token::TokenKind::Comma)
+                    .is_none()
+                {
+                    return false;
+                }
+                let Some(word) = self.word() else {
+                    return false;
+                };
+                // the value was parsed once, so references are tracked again for every copy
+                let value_token = self.previous();
+                self.instructions.push(HackInstruction::Word(word.clone()));
+                for _ in 1..count {
+                    self.track_value_ref(self.instructions.len(), value_token);
+                    self.instructions.push(HackInstruction::Word(word.clone()));
+                }
+                true
+            }
+            token::TokenKind::Directive(".space") => {
+                let Some(count) = self.count("Expected word count after '.space'") else {
+                    return false;
+                };
+                for _ in 0..count {
+                    self.instructions
+                        .push(HackInstruction::Word(AInstruction::Number(0)));
+                }
+                true
+            }
+            token::TokenKind::Directive(".var") => self.var_directive(),
+            token::TokenKind::Directive(".assert") => self.assert_directive(),
+            _ => {
+                self.raise_error_prev("Directive is not allowed here");
+                false
+            }
         }
     }
 
-    fn consume(&mut self, msg: &str, expected: token::TokenKind) -> Option<&Token> {
+    fn var_directive(&mut self) -> bool {
+        let Some(name) = self.consume_identifier("Expected variable name after '.var'") else {
+            return false;
+        };
+        let address = match self.peek().kind {
+            token::TokenKind::Number(address, _) if address <= Self::MAX_ADDRESS => address,
+            _ => {
+                self.raise_error_peek(&format!(
+                    "Expected variable address from 0 to {} after variable name",
+                    Self::MAX_ADDRESS
+                ));
+                return false;
+            }
+        };
+        self.advance();
+        if self.is_predefined_ident(name) {
+            self.raise_error(
+                &format!("Identifier {} is predefined and cannot be redefined", name),
+                self.curr - 2,
+            );
+        } else if self.ident_map.contains_key(name) {
+            self.raise_error(
+                &format!("Cannot place variable {}, it is already defined", name),
+                self.curr - 2,
+            );
+        } else {
+            self.ident_map.insert(Cow::Borrowed(name), address);
+            self.placed_vars.insert(address, name);
+            self.variables.push(Variable {
+                name: Cow::Borrowed(name),
+                address,
+                location: Self::token_location(&self.tokens[self.curr - 2]),
+                allocated: false,
+            });
+        }
+        true
+    }
+
+    fn assert_directive(&mut self) -> bool {
+        let directive = self.previous();
+        let expr_start = self.peek();
+        let (expr, len) = match Expr::parse(&self.tokens[self.curr..]) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                self.errors.push(ErrorFormatter::err_from_token(
+                    &err.message,
+                    self.source,
+                    &err.token,
+                ));
+                return false;
+            }
+        };
+        for _ in 0..len {
+            self.advance();
+        }
+        let expr_end = self.previous();
+        let message = if self.check(token::TokenKind::Comma) {
+            self.advance();
+            if let token::TokenKind::Str(message) = self.peek().kind {
+                self.advance();
+                Some(message)
+            } else {
+                self.raise_error_peek("Expected message string after ','");
+                return false;
+            }
+        } else {
+            None
+        };
+        self.assertions.push(Assertion {
+            directive,
+            expr_start,
+            expr_end,
+            expr,
+            message,
+            scope: self.label_scope,
+        });
+        true
+    }
+
+    fn track_value_ref(&mut self, id: usize, token: &'a Token<'a>) {
+        match token.kind {
+            token::TokenKind::Identifier(ident) => {
+                if ident.starts_with('.') {
+                    self.local_label_refs.push((id, token));
+                }
+                self.var_a_ins_indices.push((id, token));
+            }
+            token::TokenKind::ForwardLabel(_, _) => self.forward_label_refs.push((id, token)),
+            _ => {}
+        }
+    }
+
+    fn word(&mut self) -> Option<AInstruction<'a>> {
+        if self.check(token::TokenKind::Minus) {
+            self.advance();
+            if let token::TokenKind::Number(num, _) = self.peek().kind {
+                self.advance();
+                return Some(AInstruction::Number(num.wrapping_neg()));
+            }
+            self.raise_error_peek("Expected number after '-'");
+            return None;
+        }
+        self.value("Expected number or label as data word")
+    }
+
+    fn count(&mut self, msg: &str) -> Option<u16> {
+        if let token::TokenKind::Number(count, _) = self.peek().kind {
+            self.advance();
+            Some(count)
+        } else {
+            self.raise_error_peek(msg);
+            None
+        }
+    }
+
+    fn is_pseudo_instruction(&self) -> bool {
+        match self.peek().kind {
+            token::TokenKind::Identifier("goto" | "if" | "inc" | "dec") => true,
+            token::TokenKind::M if self.check_next(token::TokenKind::LeftBracket) => true,
+            kind if kind.is_dest_keyword() && self.check_next(token::TokenKind::Equals) => {
+                match self.peek_at(2) {
+                    token::TokenKind::Number(n, _) => n > 1,
+                    token::TokenKind::M => self.peek_at(3) == token::TokenKind::LeftBracket,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn allow_extension(&mut self, name: &str) -> bool {
+        if self.options.strict {
+            self.raise_error_peek(&format!("{} are not allowed in strict mode", name));
+            false
+        } else {
+            true
+        }
+    }
+
+    fn allow_shift(&mut self, token_id: usize) -> bool {
+        if !cfg!(feature = "extended-isa") {
+            self.raise_error(
+                "Shift instructions require building with the 'extended-isa' cargo feature",
+                token_id,
+            );
+            false
+        } else if self.options.strict {
+            self.raise_error(
+                "Shift instructions are not allowed in strict mode",
+                token_id,
+            );
+            false
+        } else if !self.options.extended_isa {
+            self.raise_error(
+                "Shift instructions require the --extended-isa flag",
+                token_id,
+            );
+            false
+        } else {
+            true
+        }
+    }
+
+    // pseudo-instructions expand to a few canonical instructions, pushed in place
+    fn pseudo_instruction(&mut self) -> bool {
+        if !self.allow_extension("Pseudo-instructions") {
+            return false;
+        }
+        match self.peek().kind {
+            token::TokenKind::Identifier("goto") => self.goto_pseudo(),
+            token::TokenKind::Identifier("if") => self.if_goto_pseudo(),
+            token::TokenKind::Identifier(_) => self.inc_dec_pseudo(),
+            token::TokenKind::M if self.check_next(token::TokenKind::LeftBracket) => {
+                self.store_pseudo()
+            }
+            _ => self.load_pseudo(),
+        }
+    }
+
+    fn goto_pseudo(&mut self) -> bool {
+        self.advance(); // skip 'goto'
+        self.push_address("Expected label or address after 'goto'")
+            && self.push_c_instruction(None, Comp::Zero, Some(token::TokenKind::Jmp))
+    }
+
+    fn if_goto_pseudo(&mut self) -> bool {
+        self.advance(); // skip 'if'
+        if self
+            .consume("Expected 'D' after 'if'", token::TokenKind::D)
+            .is_none()
+        {
+            return false;
+        }
+        let jump = match self.peek().kind {
+            token::TokenKind::Greater => token::TokenKind::Jgt,
+            token::TokenKind::GreaterEqual => token::TokenKind::Jge,
+            token::TokenKind::Less => token::TokenKind::Jlt,
+            token::TokenKind::LessEqual => token::TokenKind::Jle,
+            token::TokenKind::Equals | token::TokenKind::EqualEqual => token::TokenKind::Jeq,
+            token::TokenKind::NotEqual => token::TokenKind::Jne,
+            _ => {
+                self.raise_error_peek("Expected comparison like 'D>0' after 'if'");
+                return false;
+            }
+        };
+        self.advance();
+        if !matches!(self.peek().kind, token::TokenKind::Number(0, _)) {
+            self.raise_error_peek("Conditional jumps can only compare D with 0");
+            return false;
+        }
+        self.advance();
+        if !self.check(token::TokenKind::Identifier("goto")) {
+            self.raise_error_peek("Expected 'goto' after condition");
+            return false;
+        }
+        self.advance();
+        self.push_address("Expected label or address after 'goto'")
+            && self.push_c_instruction(None, Comp::D, Some(jump))
+    }
+
+    fn inc_dec_pseudo(&mut self) -> bool {
+        let inc = self.check(token::TokenKind::Identifier("inc"));
+        self.advance(); // skip 'inc' or 'dec'
+        let register = self.peek().kind;
+        if register == token::TokenKind::M && self.check_next(token::TokenKind::LeftBracket) {
+            if !self.push_memory_address() {
+                return false;
+            }
+        } else if matches!(
+            register,
+            token::TokenKind::A | token::TokenKind::D | token::TokenKind::M
+        ) {
+            self.advance();
+        } else {
+            self.raise_error_peek("Expected register or M[address] after 'inc' or 'dec'");
+            return false;
+        }
+        let comp = match (register, inc) {
+            (token::TokenKind::A, true) => Comp::APlusOne,
+            (token::TokenKind::A, false) => Comp::AMinusOne,
+            (token::TokenKind::D, true) => Comp::DPlusOne,
+            (token::TokenKind::D, false) => Comp::DMinusOne,
+            (_, true) => Comp::MPlusOne,
+            (_, false) => Comp::MMinusOne,
+        };
+        self.push_c_instruction(Some(register), comp, None)
+    }
+
+    fn store_pseudo(&mut self) -> bool {
+        if !self.push_memory_address() {
+            return false;
+        }
+        if self
+            .consume("Expected '=' after M[address]", token::TokenKind::Equals)
+            .is_none()
+        {
+            return false;
+        }
+        // the statement ends here, so don't let `comp` step onto the next line
+        if self.check_line_end() {
+            self.raise_error_peek("Expected value after '='");
+            return false;
+        }
+        self.advance();
+        let Some(comp) = self.comp() else {
+            return false;
+        };
+        if comp.uses_a() {
+            self.raise_error_prev("Computation cannot use A, it holds the address of M[...]");
+            return false;
+        }
+        self.push_c_instruction(Some(token::TokenKind::M), comp, None)
+    }
+
+    fn load_pseudo(&mut self) -> bool {
+        let dest = self.peek().kind;
+        self.advance(); // skip destination
+        self.advance(); // skip '='
+        if let token::TokenKind::Number(_, _) = self.peek().kind {
+            if dest.writes_memory() {
+                self.raise_error_peek(
+                    "Cannot load a constant into M, '@' would overwrite the address in A",
+                );
+                return false;
+            }
+            // the constant goes through `@`, so it has to fit in an address
+            if !self.push_address("Expected value after '='") {
+                return false;
+            }
+            // `@value` alone already loads A
+            dest == token::TokenKind::A || self.push_c_instruction(Some(dest), Comp::A, None)
+        } else {
+            self.push_memory_address() && self.push_c_instruction(Some(dest), Comp::M, None)
+        }
+    }
+
+    fn push_memory_address(&mut self) -> bool {
+        self.advance(); // skip 'M'
+        self.advance(); // skip '['
+        self.push_address("Expected label or address inside 'M[...]'")
+            && self
+                .consume("Expected ']' after address", token::TokenKind::RightBracket)
+                .is_some()
+    }
+
+    fn push_address(&mut self, msg: &str) -> bool {
+        if let Some(ins) = self.address(msg) {
+            self.instructions.push(ins);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn push_c_instruction(
+        &mut self,
+        dest: Option<token::TokenKind<'a>>,
+        comp: Comp,
+        jump: Option<token::TokenKind<'a>>,
+    ) -> bool {
+        self.instructions
+            .push(HackInstruction::C(CInstruction::new(dest, comp, jump)));
+        true
+    }
+
+    fn consume(&mut self, msg: &str, expected: token::TokenKind) -> Option<&Token<'a>> {
         if expected == self.peek().kind {
             self.advance();
             Some(self.previous())
@@ -272,10 +1125,22 @@ impl<'a> Parser<'a> {
         self.advance();
         let dest = if self.check(token::TokenKind::Equals) {
             if !self.previous().kind.is_dest_keyword() {
-                self.raise_error_prev("Expected destination after '='");
+                let help = match self.previous().kind {
+                    token::TokenKind::Identifier(name) => {
+                        suggestions::dest_register(name).or_else(|| {
+                            // every single letter is one edit away from some register
+                            (name.len() > 1)
+                                .then(|| suggestions::closest(name, ["MD", "AM", "AD", "AMD"]))
+                                .flatten()
+                                .map(String::from)
+                        })
+                    }
+                    _ => None,
+                };
+                self.raise_error_with_help("Expected destination after '='", self.curr - 1, help);
                 return None;
             }
-            let t = &self.tokens[self.curr - 1];
+            let t = self.tokens[self.curr - 1].kind;
             self.advance(); // skip '='
             self.advance();
             Some(t)
@@ -287,11 +1152,17 @@ impl<'a> Parser<'a> {
             self.advance(); //skip semicolon
             if !self.peek().kind.is_jump_keyword() {
                 // semicolon is the previous token, and we report the error at next (newline) token so it appears after the semicolon
-                self.raise_error_peek("Expected jump keyword after ';'");
+                let help = match self.peek().kind {
+                    token::TokenKind::Identifier(name) => {
+                        suggestions::closest(name, Self::JUMP_MNEMONICS).map(String::from)
+                    }
+                    _ => None,
+                };
+                self.raise_error_with_help("Expected jump keyword after ';'", self.curr, help);
                 return None;
             }
             self.advance();
-            let t = &self.tokens[self.curr - 1];
+            let t = self.tokens[self.curr - 1].kind;
             Some(t)
         } else {
             None
@@ -310,6 +1181,13 @@ impl<'a> Parser<'a> {
             .iter()
             .map(|token| token.kind)
             .collect::<Vec<token::TokenKind>>();
+        if matches!(
+            tokens.get(1),
+            Some(token::TokenKind::ShiftLeft | token::TokenKind::ShiftRight)
+        ) && !self.allow_shift(start + 1)
+        {
+            return None;
+        }
 
         if let Some(comp) = Comp::from_tokens(tokens) {
             for _ in 0..comp.len() - 1 {
@@ -349,11 +1227,21 @@ impl<'a> Parser<'a> {
         matches!(&self.peek().kind, x if *x == token_kind)
     }
 
-    fn previous(&self) -> &Token<'a> {
+    fn check_next(&self, token_kind: token::TokenKind) -> bool {
+        self.peek_at(1) == token_kind
+    }
+
+    fn peek_at(&self, offset: usize) -> token::TokenKind<'a> {
+        self.tokens
+            .get(self.curr + offset)
+            .map_or(token::TokenKind::Eof, |token| token.kind)
+    }
+
+    fn previous(&self) -> &'a Token<'a> {
         &self.tokens[self.curr - 1]
     }
 
-    fn peek(&self) -> &Token<'a> {
+    fn peek(&self) -> &'a Token<'a> {
         &self.tokens[self.curr]
     }
 
@@ -367,6 +1255,16 @@ impl<'a> Parser<'a> {
         self.errors
             .push(ErrorFormatter::err_from_token(msg, self.source, token));
     }
+    fn raise_error_with_help(&mut self, msg: &str, token_id: usize, suggestion: Option<String>) {
+        let token = &self.tokens[token_id];
+        let mut error = ErrorFormatter::err_from_token(msg, self.source, token);
+        if let Some(suggestion) = suggestion {
+            error =
+                ErrorFormatter::add_note(error, "help", &format!("did you mean `{}`?", suggestion));
+        }
+        self.errors.push(error);
+    }
+
     fn raise_error_prev(&mut self, msg: &str) {
         self.raise_error(msg, self.curr - 1);
     }
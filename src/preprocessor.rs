@@ -0,0 +1,202 @@
+use crate::{
+    error_formatting::ErrorFormatter,
+    expression::{Expr, ExprError},
+    scanner::token::{Token, TokenKind},
+    Error, Result,
+};
+
+struct Block<'a> {
+    directive: Token<'a>,
+    parent_active: bool,
+    condition: bool,
+    in_else: bool,
+}
+
+impl<'a> Block<'a> {
+    fn is_active(&self) -> bool {
+        self.parent_active && self.condition != self.in_else
+    }
+}
+
+pub struct Preprocessor<'a> {
+    source: &'a [&'a str],
+    tokens: Vec<Token<'a>>,
+    defines: &'a [(String, u16)],
+    curr: usize,
+    output: Vec<Token<'a>>,
+    blocks: Vec<Block<'a>>,
+    errors: Vec<Error>,
+}
+
+impl<'a> Preprocessor<'a> {
+    pub fn new(
+        tokens: Vec<Token<'a>>,
+        source: &'a [&'a str],
+        defines: &'a [(String, u16)],
+    ) -> Self {
+        Self {
+            source,
+            output: Vec::with_capacity(tokens.len()),
+            tokens,
+            defines,
+            curr: 0,
+            blocks: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn run(mut self) -> Result<Vec<Token<'a>>> {
+        while self.tokens[self.curr].kind != TokenKind::Eof {
+            self.line();
+        }
+        let eof = self.tokens[self.curr];
+        for block in std::mem::take(&mut self.blocks) {
+            if let TokenKind::Directive(name) = block.directive.kind {
+                self.raise_error(
+                    &format!("Unterminated '{}', expected '.endif'", name),
+                    &block.directive,
+                );
+            }
+        }
+        self.output.push(eof);
+
+        if self.errors.is_empty() {
+            Ok(self.output)
+        } else {
+            for error in &self.errors {
+                eprintln!("{}", error);
+            }
+            Err(Error::from(format!(
+                "Encountered {} errors, aborting compilation",
+                self.errors.len()
+            )))
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.blocks.last().is_none_or(|block| block.is_active())
+    }
+
+    fn line(&mut self) {
+        let start = self.curr;
+        while !matches!(
+            self.tokens[self.curr].kind,
+            TokenKind::NewLine | TokenKind::Eof
+        ) {
+            self.curr += 1;
+        }
+        let end = self.curr;
+        if self.tokens[self.curr].kind == TokenKind::NewLine {
+            self.curr += 1;
+        }
+
+        let first = self.tokens[start];
+        match first.kind {
+            TokenKind::Directive(".if" | ".ifdef" | ".else" | ".endif") => {
+                self.directive(first, start + 1, end)
+            }
+            _ if self.is_active() => self
+                .output
+                .extend_from_slice(&self.tokens[start..self.curr]),
+            _ => {}
+        }
+    }
+
+    fn directive(&mut self, directive: Token<'a>, args_start: usize, line_end: usize) {
+        let args_end = match directive.kind {
+            TokenKind::Directive(".if") => self.if_directive(directive, args_start),
+            TokenKind::Directive(".ifdef") => self.ifdef_directive(directive, args_start),
+            TokenKind::Directive(".else") => {
+                self.else_directive(directive);
+                Some(args_start)
+            }
+            _ => {
+                if self.blocks.pop().is_none() {
+                    self.raise_error("'.endif' without matching '.if'", &directive);
+                }
+                Some(args_start)
+            }
+        };
+        if let Some(args_end) = args_end {
+            if args_end != line_end {
+                let token = self.tokens[args_end];
+                self.raise_error("Unexpected token after directive", &token);
+            }
+        }
+    }
+
+    fn if_directive(&mut self, directive: Token<'a>, args_start: usize) -> Option<usize> {
+        let parent_active = self.is_active();
+        let (expr, len) = match Expr::parse(&self.tokens[args_start..]) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                self.raise_expr_error(err);
+                (Expr::Number(0), 0)
+            }
+        };
+        // an inactive block may test symbols that are only defined for other targets
+        let condition = parent_active && self.eval(&expr);
+        self.blocks.push(Block {
+            directive,
+            parent_active,
+            condition,
+            in_else: false,
+        });
+        (len > 0).then_some(args_start + len)
+    }
+
+    fn ifdef_directive(&mut self, directive: Token<'a>, args_start: usize) -> Option<usize> {
+        let condition = match self.tokens[args_start].kind {
+            TokenKind::Identifier(name) => self.defines.iter().any(|(define, _)| define == name),
+            _ => {
+                let token = self.tokens[args_start];
+                self.raise_error("Expected symbol name after '.ifdef'", &token);
+                false
+            }
+        };
+        self.blocks.push(Block {
+            directive,
+            parent_active: self.is_active(),
+            condition,
+            in_else: false,
+        });
+        matches!(self.tokens[args_start].kind, TokenKind::Identifier(_)).then_some(args_start + 1)
+    }
+
+    fn else_directive(&mut self, directive: Token<'a>) {
+        match self.blocks.last_mut() {
+            Some(block) if block.in_else => {
+                self.raise_error("Duplicate '.else' in conditional block", &directive)
+            }
+            Some(block) => block.in_else = true,
+            None => self.raise_error("'.else' without matching '.if'", &directive),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr<'a>) -> bool {
+        // like the C preprocessor, symbols that were not defined evaluate to 0
+        let defines = self.defines;
+        let lookup = |name: &str| {
+            defines
+                .iter()
+                .find(|(define, _)| define == name)
+                .map_or(Some(0), |(_, value)| Some(*value as i64))
+        };
+        match expr.eval(&lookup) {
+            Ok(value) => value != 0,
+            Err(err) => {
+                self.raise_expr_error(err);
+                false
+            }
+        }
+    }
+
+    fn raise_expr_error(&mut self, err: ExprError<'a>) {
+        self.raise_error(&err.message, &err.token);
+    }
+
+    fn raise_error(&mut self, msg: &str, token: &Token) {
+        self.errors
+            .push(ErrorFormatter::err_from_token(msg, self.source, token));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{error_formatting::ErrorFormatter, Error};
+use crate::{
+    ast::Location, error_formatting::ErrorFormatter, lints::Suppression, suggestions, Error,
+};
 
 use self::token::Token;
 pub mod token;
@@ -10,10 +12,16 @@ pub struct Scanner<'a> {
     start: usize,
     line: usize,
     errors: Vec<Error>,
+    suppressions: Vec<Suppression<'a>>,
+    // suppressions on their own line, waiting for the next statement
+    pending_suppressions: Vec<usize>,
 }
 
 impl<'a> Scanner<'a> {
-    const MAX_ADDRESS: usize = 32767;
+    const MAX_WORD: usize = 65535;
+    const DIRECTIVES: [&'static str; 9] = [
+        ".if", ".ifdef", ".else", ".endif", ".word", ".fill", ".space", ".var", ".assert",
+    ];
     pub fn new(source: &'a [&'a str]) -> Self {
         Self {
             source,
@@ -22,6 +30,8 @@ impl<'a> Scanner<'a> {
             start: 0,
             line: 0,
             errors: Vec::new(),
+            suppressions: Vec::new(),
+            pending_suppressions: Vec::new(),
         }
     }
 
@@ -42,6 +52,11 @@ impl<'a> Scanner<'a> {
     }
 
     fn add_token(&mut self, token_type: token::TokenKind<'a>) {
+        if token_type != token::TokenKind::NewLine {
+            for id in self.pending_suppressions.drain(..) {
+                self.suppressions[id].line = self.line;
+            }
+        }
         let token = Token::new(token_type, self.line, self.start);
         self.tokens.push(token);
     }
@@ -60,6 +75,15 @@ impl<'a> Scanner<'a> {
         }
     }
 
+    fn peek_next(&self) -> u8 {
+        if self.is_at_end() {
+            b'\0'
+        } else {
+            let line = self.source[self.line].as_bytes();
+            line.get(self.curr + 1).copied().unwrap_or(b'\n')
+        }
+    }
+
     fn match_next(&mut self, expected: u8) -> bool {
         if self.is_at_end() {
             return false;
@@ -71,20 +95,55 @@ impl<'a> Scanner<'a> {
         true
     }
     fn number(&mut self) {
-        while self.peek().is_ascii_digit() || self.peek() == b'_' {
-            self.advance();
+        let radix = match (self.curr_lexeme(), self.peek()) {
+            ("0", b'x' | b'X') => 16,
+            ("0", b'b' | b'B') if self.peek_next().is_ascii_digit() => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.advance(); // skip radix prefix
         }
-        let literal: usize = self.curr_lexeme().parse().unwrap();
-        if literal > Self::MAX_ADDRESS {
-            self.raise_error(&format!(
-                "Address out of range. Address ranges from 0 to {}",
-                Self::MAX_ADDRESS
-            ));
+        while self.peek().is_ascii_hexdigit() && radix == 16
+            || self.peek().is_ascii_digit()
+            || self.peek() == b'_'
+        {
+            self.advance();
         }
-        self.add_token(token::TokenKind::Number(
-            literal as u16,
-            (self.curr - self.start) as u8,
-        ));
+        let digits = match radix {
+            10 => self.curr_lexeme().replace('_', ""),
+            _ => self.curr_lexeme()[2..].replace('_', ""),
+        };
+        let literal = match usize::from_str_radix(&digits, radix) {
+            Ok(literal) if literal <= Self::MAX_WORD => literal,
+            Ok(_) => {
+                self.raise_error(&format!(
+                    "Number out of range. Numbers range from 0 to {}",
+                    Self::MAX_WORD
+                ));
+                0
+            }
+            Err(_) => {
+                self.raise_error("Invalid number literal");
+                0
+            }
+        };
+        let len = (self.curr - self.start) as u8;
+        let token_type = match self.peek() {
+            b'b' | b'f' if radix == 10 && !Self::is_identifier_char(self.peek_next()) => {
+                let forward = self.advance() == b'f';
+                if forward {
+                    token::TokenKind::ForwardLabel(literal as u16, len + 1)
+                } else {
+                    token::TokenKind::BackwardLabel(literal as u16, len + 1)
+                }
+            }
+            _ => token::TokenKind::Number(literal as u16, len),
+        };
+        self.add_token(token_type);
+    }
+
+    fn is_identifier_char(char: u8) -> bool {
+        matches!(char, b'_' | b'.' | b'$' | b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9')
     }
 
     fn curr_lexeme(&self) -> &'a str {
@@ -92,7 +151,7 @@ impl<'a> Scanner<'a> {
     }
 
     fn identifier(&mut self) {
-        while matches!(self.peek(), b'_' | b'.' | b'$' | b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9') {
+        while Self::is_identifier_char(self.peek()) {
             self.advance();
         }
         let token_type = match self.curr_lexeme() {
@@ -115,6 +174,83 @@ impl<'a> Scanner<'a> {
         self.add_token(token_type);
     }
 
+    fn directive_or_local_label(&mut self) {
+        while Self::is_identifier_char(self.peek()) {
+            self.advance();
+        }
+        let lexeme = self.curr_lexeme();
+        if Self::DIRECTIVES.contains(&lexeme) {
+            self.add_token(token::TokenKind::Directive(lexeme));
+        } else if self.at_statement_start() {
+            // local labels are only declared in parentheses, so this must be a directive
+            let message = match suggestions::closest(lexeme, Self::DIRECTIVES) {
+                Some(similar) => format!(
+                    "Unknown directive '{}', did you mean '{}'?",
+                    lexeme, similar
+                ),
+                None => format!("Unknown directive '{}'", lexeme),
+            };
+            self.raise_error(&message);
+        } else {
+            self.add_token(token::TokenKind::Identifier(lexeme));
+        }
+    }
+
+    fn at_statement_start(&self) -> bool {
+        self.tokens
+            .last()
+            .is_none_or(|token| token.kind == token::TokenKind::NewLine)
+    }
+
+    fn string(&mut self) {
+        while !self.is_at_line_end() && self.peek() != b'"' {
+            self.advance();
+        }
+        if self.is_at_line_end() {
+            self.raise_error("Unterminated string, expected '\"' before the end of the line");
+            return;
+        }
+        self.advance(); // skip closing quote
+        let content = &self.source[self.line][self.start + 1..self.curr - 1];
+        self.add_token(token::TokenKind::Str(content));
+    }
+
+    fn comment(&mut self) {
+        let text = &self.source[self.line][self.curr..];
+        // anything but a well-formed `hackasm: allow(...)` is an ordinary comment
+        if let Some(lints) = text
+            .trim_start()
+            .strip_prefix("hackasm:")
+            .and_then(|rest| rest.trim_start().strip_prefix("allow("))
+            .and_then(|rest| rest.split_once(')'))
+            .map(|(lints, _)| lints.split(',').map(str::trim).collect::<Vec<_>>())
+            .filter(|lints| lints.iter().all(|lint| !lint.is_empty()))
+        {
+            self.suppression(lints);
+        }
+        self.skip_comment();
+    }
+
+    // `// hackasm: allow(unused-label, ...) justification`
+    fn suppression(&mut self, lints: Vec<&'a str>) {
+        let location = Location {
+            line: self.line,
+            start: self.start,
+            len: self.source[self.line].len() - self.start,
+        };
+        let on_statement = self.tokens.last().is_some_and(|token| {
+            token.line == self.line && token.kind != token::TokenKind::NewLine
+        });
+        if !on_statement {
+            self.pending_suppressions.push(self.suppressions.len());
+        }
+        self.suppressions.push(Suppression {
+            lints,
+            line: self.line,
+            location,
+        });
+    }
+
     fn skip_comment(&mut self) {
         while !self.is_at_line_end() && !self.is_at_end() {
             self.advance();
@@ -125,22 +261,76 @@ impl<'a> Scanner<'a> {
         let char = self.advance();
         match char {
             b'@' => self.add_token(token::TokenKind::At),
-            b'=' => self.add_token(token::TokenKind::Equals),
+            b'=' => {
+                if self.match_next(b'=') {
+                    self.add_token(token::TokenKind::EqualEqual)
+                } else {
+                    self.add_token(token::TokenKind::Equals)
+                }
+            }
             b'+' => self.add_token(token::TokenKind::Plus),
             b'-' => self.add_token(token::TokenKind::Minus),
-            b'|' => self.add_token(token::TokenKind::Or),
-            b'&' => self.add_token(token::TokenKind::And),
-            b'!' => self.add_token(token::TokenKind::Not),
+            b'|' => {
+                if self.match_next(b'|') {
+                    self.add_token(token::TokenKind::OrOr)
+                } else {
+                    self.add_token(token::TokenKind::Or)
+                }
+            }
+            b'&' => {
+                if self.match_next(b'&') {
+                    self.add_token(token::TokenKind::AndAnd)
+                } else {
+                    self.add_token(token::TokenKind::And)
+                }
+            }
+            b'!' => {
+                if self.match_next(b'=') {
+                    self.add_token(token::TokenKind::NotEqual)
+                } else {
+                    self.add_token(token::TokenKind::Not)
+                }
+            }
+            b'<' => {
+                if self.match_next(b'=') {
+                    self.add_token(token::TokenKind::LessEqual)
+                } else if self.match_next(b'<') {
+                    self.add_token(token::TokenKind::ShiftLeft)
+                } else {
+                    self.add_token(token::TokenKind::Less)
+                }
+            }
+            b'>' => {
+                if self.match_next(b'=') {
+                    self.add_token(token::TokenKind::GreaterEqual)
+                } else if self.match_next(b'>') {
+                    self.add_token(token::TokenKind::ShiftRight)
+                } else {
+                    self.add_token(token::TokenKind::Greater)
+                }
+            }
+            b'.' => {
+                if self.peek().is_ascii_alphabetic() {
+                    self.directive_or_local_label()
+                } else {
+                    self.raise_error("Unexpected character")
+                }
+            }
             b'(' => self.add_token(token::TokenKind::LeftParen),
             b')' => self.add_token(token::TokenKind::RightParen),
+            b'[' => self.add_token(token::TokenKind::LeftBracket),
+            b']' => self.add_token(token::TokenKind::RightBracket),
             b'/' => {
                 if self.match_next(b'/') {
-                    self.skip_comment();
+                    self.comment();
                 } else {
                     self.raise_error("Unexpected character, did you mean '//'?")
                 }
             }
             b';' => self.add_token(token::TokenKind::Semicolon),
+            b':' => self.add_token(token::TokenKind::Colon),
+            b',' => self.add_token(token::TokenKind::Comma),
+            b'"' => self.string(),
             b'\n' => {
                 if let Some(t) = self.tokens.last() {
                     if t.kind != token::TokenKind::NewLine {
@@ -182,11 +372,11 @@ impl<'a> Scanner<'a> {
         }
     }
 
-    pub fn run(mut self) -> crate::Result<Vec<Token<'a>>> {
+    pub fn run(mut self) -> crate::Result<(Vec<Token<'a>>, Vec<Suppression<'a>>)> {
         if self.scan_tokens() {
             #[cfg(feature = "trace")]
             self._print_tokens();
-            Ok(self.tokens)
+            Ok((self.tokens, self.suppressions))
         } else {
             self.print_errors();
             Err(Error::from(format!(
@@ -5,8 +5,12 @@ pub enum TokenKind<'a> {
     //Symbols
     LeftParen,
     RightParen,
+    LeftBracket,
+    RightBracket,
 
     Semicolon,
+    Colon,
+    Comma,
     // Artificial
     Eof,
     NewLine,
@@ -18,6 +22,16 @@ pub enum TokenKind<'a> {
     And,
     Or,
     Not,
+    EqualEqual,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    AndAnd,
+    OrOr,
+    ShiftLeft,
+    ShiftRight,
 
     // keywords
     M,
@@ -38,9 +52,14 @@ pub enum TokenKind<'a> {
 
     // identifiers
     Identifier(&'a str),
+    Directive(&'a str),
 
     // Literals
     Number(u16, u8),
+    Str(&'a str),
+    // references to numeric labels, like `1b` and `1f`
+    BackwardLabel(u16, u8),
+    ForwardLabel(u16, u8),
 }
 
 impl<'a> TokenKind<'a> {
@@ -57,6 +76,65 @@ impl<'a> TokenKind<'a> {
         )
     }
 
+    pub fn as_bin_code(&self) -> String {
+        match self {
+            // A-bit, D-bit, M-bit
+            TokenKind::M => "001".to_string(),
+            TokenKind::D => "010".to_string(),
+            TokenKind::Md => "011".to_string(),
+            TokenKind::A => "100".to_string(),
+            TokenKind::Am => "101".to_string(),
+            TokenKind::Ad => "110".to_string(),
+            TokenKind::Amd => "111".to_string(),
+
+            TokenKind::Jgt => "001".to_string(),
+            TokenKind::Jeq => "010".to_string(),
+            TokenKind::Jge => "011".to_string(),
+            TokenKind::Jlt => "100".to_string(),
+            TokenKind::Jne => "101".to_string(),
+            TokenKind::Jle => "110".to_string(),
+            TokenKind::Jmp => "111".to_string(),
+            TokenKind::Number(n, _) => format!("{:#016b}", n),
+            _ => panic!("{}", format!("Cannot convert '{:?}' to heck binary", self)),
+        }
+    }
+
+    // assembly text of destination and jump keywords
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            TokenKind::M => "M",
+            TokenKind::D => "D",
+            TokenKind::Md => "MD",
+            TokenKind::A => "A",
+            TokenKind::Am => "AM",
+            TokenKind::Ad => "AD",
+            TokenKind::Amd => "AMD",
+
+            TokenKind::Jgt => "JGT",
+            TokenKind::Jeq => "JEQ",
+            TokenKind::Jge => "JGE",
+            TokenKind::Jlt => "JLT",
+            TokenKind::Jne => "JNE",
+            TokenKind::Jle => "JLE",
+            TokenKind::Jmp => "JMP",
+            _ => panic!("{}", format!("'{:?}' is not a dest or jump keyword", self)),
+        }
+    }
+
+    pub fn writes_memory(&self) -> bool {
+        matches!(
+            self,
+            TokenKind::M | TokenKind::Md | TokenKind::Am | TokenKind::Amd
+        )
+    }
+
+    pub fn writes_a(&self) -> bool {
+        matches!(
+            self,
+            TokenKind::A | TokenKind::Am | TokenKind::Ad | TokenKind::Amd
+        )
+    }
+
     pub fn is_dest_keyword(&self) -> bool {
         matches!(
             self,
@@ -86,41 +164,25 @@ impl<'a> Token<'a> {
             start,
         }
     }
-    pub fn as_bin_code(&self) -> String {
-        match self.kind {
-            // A-bit, D-bit, M-bit
-            TokenKind::M => "001".to_string(),
-            TokenKind::D => "010".to_string(),
-            TokenKind::Md => "011".to_string(),
-            TokenKind::A => "100".to_string(),
-            TokenKind::Am => "101".to_string(),
-            TokenKind::Ad => "110".to_string(),
-            TokenKind::Amd => "111".to_string(),
-
-            TokenKind::Jgt => "001".to_string(),
-            TokenKind::Jeq => "010".to_string(),
-            TokenKind::Jge => "011".to_string(),
-            TokenKind::Jlt => "100".to_string(),
-            TokenKind::Jne => "101".to_string(),
-            TokenKind::Jle => "110".to_string(),
-            TokenKind::Jmp => "111".to_string(),
-            TokenKind::Number(n, _) => format!("{:#016b}", n),
-            _ => panic!("{}", format!("Cannot convert '{}' to heck binary", self)),
-        }
-    }
-
     pub fn len(&self) -> usize {
         match self.kind {
-            TokenKind::Number(_, len) => len as usize,
-            TokenKind::Identifier(s) => s.len(),
+            TokenKind::Number(_, len)
+            | TokenKind::BackwardLabel(_, len)
+            | TokenKind::ForwardLabel(_, len) => len as usize,
+            TokenKind::Identifier(s) | TokenKind::Directive(s) => s.len(),
+            TokenKind::Str(s) => s.len() + 2,
             TokenKind::Eof => 0,
             TokenKind::NewLine
             | TokenKind::A
             | TokenKind::D
             | TokenKind::M
             | TokenKind::Semicolon
+            | TokenKind::Colon
+            | TokenKind::Comma
             | TokenKind::LeftParen
             | TokenKind::RightParen
+            | TokenKind::LeftBracket
+            | TokenKind::RightBracket
             | TokenKind::At
             | TokenKind::Equals
             | TokenKind::Plus
@@ -128,7 +190,18 @@ impl<'a> Token<'a> {
             | TokenKind::And
             | TokenKind::Or
             | TokenKind::Not => 1,
-            TokenKind::Md | TokenKind::Am | TokenKind::Ad => 2,
+            TokenKind::Less | TokenKind::Greater => 1,
+            TokenKind::Md
+            | TokenKind::Am
+            | TokenKind::Ad
+            | TokenKind::EqualEqual
+            | TokenKind::NotEqual
+            | TokenKind::LessEqual
+            | TokenKind::GreaterEqual
+            | TokenKind::AndAnd
+            | TokenKind::OrOr
+            | TokenKind::ShiftLeft
+            | TokenKind::ShiftRight => 2,
             TokenKind::Amd
             | TokenKind::Jgt
             | TokenKind::Jeq
@@ -147,7 +220,11 @@ impl<'a> Display for Token<'a> {
         match &self.kind {
             TokenKind::LeftParen => write!(f, "LeftParen"),
             TokenKind::RightParen => write!(f, "RightParen"),
+            TokenKind::LeftBracket => write!(f, "LeftBracket"),
+            TokenKind::RightBracket => write!(f, "RightBracket"),
             TokenKind::Semicolon => write!(f, "Semicolon"),
+            TokenKind::Colon => write!(f, "Colon"),
+            TokenKind::Comma => write!(f, "Comma"),
             TokenKind::Eof => write!(f, "Eof"),
             TokenKind::NewLine => write!(f, "NewLine"),
             TokenKind::At => write!(f, "At"),
@@ -157,6 +234,16 @@ impl<'a> Display for Token<'a> {
             TokenKind::And => write!(f, "And"),
             TokenKind::Or => write!(f, "Or"),
             TokenKind::Not => write!(f, "Not"),
+            TokenKind::EqualEqual => write!(f, "EqualEqual"),
+            TokenKind::NotEqual => write!(f, "NotEqual"),
+            TokenKind::Less => write!(f, "Less"),
+            TokenKind::LessEqual => write!(f, "LessEqual"),
+            TokenKind::Greater => write!(f, "Greater"),
+            TokenKind::GreaterEqual => write!(f, "GreaterEqual"),
+            TokenKind::AndAnd => write!(f, "AndAnd"),
+            TokenKind::OrOr => write!(f, "OrOr"),
+            TokenKind::ShiftLeft => write!(f, "ShiftLeft"),
+            TokenKind::ShiftRight => write!(f, "ShiftRight"),
             TokenKind::M => write!(f, "M"),
             TokenKind::D => write!(f, "D"),
             TokenKind::Md => write!(f, "Md"),
@@ -172,7 +259,11 @@ impl<'a> Display for Token<'a> {
             TokenKind::Jle => write!(f, "Jle"),
             TokenKind::Jmp => write!(f, "Jmp"),
             TokenKind::Identifier(s) => write!(f, "Identifier: {}", s),
+            TokenKind::Directive(s) => write!(f, "Directive: {}", s),
             TokenKind::Number(n, _) => write!(f, "Number: {}", n),
+            TokenKind::Str(s) => write!(f, "Str: {:?}", s),
+            TokenKind::BackwardLabel(n, _) => write!(f, "BackwardLabel: {}b", n),
+            TokenKind::ForwardLabel(n, _) => write!(f, "ForwardLabel: {}f", n),
         }?;
         write!(f, ", start {}>", self.start)
     }
@@ -0,0 +1,55 @@
+use std::cmp;
+
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.bytes().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + (ca != cb) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = cmp::min(substitution, cmp::min(row[j], row[j + 1]) + 1);
+        }
+    }
+    row[b.len()]
+}
+
+// a name differing only in case always wins, otherwise roughly a third of the name may be wrong
+pub fn closest<'b, I>(name: &str, candidates: I) -> Option<&'b str>
+where
+    I: IntoIterator<Item = &'b str>,
+{
+    let max_distance = cmp::max(1, name.len() / 3);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| {
+            let distance = if candidate.eq_ignore_ascii_case(name) {
+                0
+            } else {
+                edit_distance(name, candidate)
+            };
+            (distance, candidate)
+        })
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+// destination registers are always written in A, M, D order
+pub fn dest_register(name: &str) -> Option<String> {
+    let upper = name.to_ascii_uppercase();
+    let mut letters: Vec<char> = upper.chars().collect();
+    letters.sort_unstable();
+    letters.dedup();
+    if letters.len() != upper.len() || !letters.iter().all(|c| matches!(c, 'A' | 'D' | 'M')) {
+        return None;
+    }
+    Some(
+        ['A', 'M', 'D']
+            .iter()
+            .filter(|c| letters.contains(c))
+            .collect(),
+    )
+}
@@ -3,27 +3,36 @@ use rusty_hack_asm::Result;
 use std::fs::{self};
 
 fn run_binary(file: &str) -> assert::Assert {
+    run_binary_with(&[file])
+}
+
+fn run_binary_with(args: &[&str]) -> assert::Assert {
     assert_cmd::Command::cargo_bin("rusty_hack_asm")
         .unwrap()
-        .arg(file)
+        .args(args)
         .assert()
 }
 
+// the expected files use LF, the default output keeps the CRLF of earlier versions
 fn compile_and_compare(filename: &str) -> Result<()> {
     let source_file = format!("tests/data/asm/{}.asm", filename);
 
     run_binary(&source_file).success();
-    compare(filename)
+    compare(filename, "\r\n")?;
+    run_binary_with(&["--line-ending", "lf", &source_file]).success();
+    compare(filename, "\n")
 }
 
-fn compare(base_filename: &str) -> Result<()> {
+fn compare(base_filename: &str, line_ending: &str) -> Result<()> {
     let bin_file = format!("tests/data/asm/{}.hack", base_filename);
     let expected_file = format!("tests/data/expected/{}.hack", base_filename);
+    let expected = fs::read_to_string(&expected_file)?.replace('\n', line_ending);
 
     assert!(
-        fs::read_to_string(&bin_file)? == fs::read_to_string(&expected_file)?,
-        "generated file {}.asm does not match expected data",
-        base_filename
+        fs::read_to_string(&bin_file)? == expected,
+        "generated file {}.asm does not match expected data with {:?} line endings",
+        base_filename,
+        line_ending
     );
     Ok(())
 }
@@ -61,3 +70,11 @@ fn test_compilation_pong_symbolless() -> Result<()> {
 fn test_compilation_rect_symbolless() -> Result<()> {
     compile_and_compare("RectL")
 }
+
+#[test]
+fn test_compilation_reports_size() {
+    // a different format than test_compilation_add, so they don't write the same file
+    run_binary_with(&["--format=bin", "tests/data/asm/Add.asm"])
+        .success()
+        .stdout(predicates::str::contains("(6 of 32768 ROM words)"));
+}
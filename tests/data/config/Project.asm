@@ -0,0 +1,18 @@
+// Uses hackasm.toml from the same directory
+
+// hackasm: allow(unused-label) entry point for the debugger
+(START)
+   @count
+   M=0
+   @0
+   D=M
+.if DEBUG
+   @count
+   M=D
+.endif
+(UNUSED)
+   @count
+   D=M
+(END)
+   @END
+   0;JMP   // hackasm: allow(jump-writes-a, warnings)
@@ -0,0 +1,7 @@
+// Denied by hackasm.toml
+
+   @total
+   D=M
+(END)
+   @END
+   0;JMP
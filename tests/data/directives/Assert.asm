@@ -0,0 +1,17 @@
+// Layout assumptions checked on every build
+
+.var BUF 1000
+.var BUF_END 4000
+(START)
+   @counter
+   M=0
+(.loop)
+   @.loop
+   0;JMP
+.assert .loop + 2 == 4
+(END)
+.assert END < 32768
+.assert BUF_END <= SCREEN, "buffer overlaps the screen"
+.assert START.loop == 2 && counter == 16
+// `&` binds tighter than `==`, unlike in C where this is `(4 == 6) & 4`
+.assert 4 == 6 & 4
@@ -0,0 +1,15 @@
+// Selects the output port depending on the target board
+
+.ifdef FPGA
+   @16384
+.else
+   @SCREEN
+.endif
+   D=A
+.if DEBUG && FPGA == 0
+   @R15
+   M=D
+.endif
+(END)
+   @END
+   0;JMP
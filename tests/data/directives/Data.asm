@@ -0,0 +1,13 @@
+// Looks up the second entry of a table stored in ROM
+
+   @TABLE
+   D=A
+   @END
+   0;JMP
+(TABLE)
+   .word 0xFFFF, 40000, TABLE, -2
+   .fill 2, 1f
+   .space 1
+1:
+(END)
+   .word 0b1010_1010, counter
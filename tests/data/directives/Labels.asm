@@ -0,0 +1,18 @@
+// Two routines reusing the same local and numeric label names
+
+(FIRST)
+(.loop)
+   @.loop
+   D;JGT
+1:
+   @1b
+   0;JMP
+(SECOND)
+(.loop)
+   @1f
+   D;JEQ
+   @FIRST.loop
+   0;JMP
+1:
+   @.loop
+   0;JMP
@@ -0,0 +1,10 @@
+// Counts down from 5 using pseudo-instructions
+
+   D=5
+   M[counter]=D
+(LOOP)
+   dec M[counter]
+   D=M[counter]
+   if D>0 goto LOOP
+   inc D
+   goto LOOP
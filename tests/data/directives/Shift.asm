@@ -0,0 +1,7 @@
+// Multiplies D by 4 and halves RAM[0] on the extended Hack CPU
+
+   D=D<<
+   D=D<<
+   @R0
+   M=M>>
+   AM=A<<;JMP
@@ -0,0 +1,9 @@
+// Statics live from 16, the stack pointer area is placed explicitly
+
+.var stack 256
+   @counter
+   M=0
+   @stack
+   M=D
+   @total
+   M=0
@@ -0,0 +1,8 @@
+.var BUF_END 20000
+   @counter
+   M=0
+(END)
+.assert END > 100
+.assert BUF_END <= SCREEN, "buffer overlaps the screen"
+.assert counter == 17
+.assert MISSING
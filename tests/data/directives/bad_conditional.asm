@@ -0,0 +1,12 @@
+.if DEBUG
+   @0
+   D=A
+.else
+   @1
+.else
+   D=A
+.endif
+.endif
+.ifdef
+.if (DEBUG
+   @2
@@ -0,0 +1,4 @@
+   @40000
+   .word
+   .fill 0, 1
+   .space X
@@ -0,0 +1,5 @@
+// A define that fits in a data word but not in an A-instruction
+
+   @BIG
+   D=A
+   .word BIG
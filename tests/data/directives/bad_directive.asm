@@ -0,0 +1,9 @@
+// Misspelled directives next to a local label, which also starts with '.'
+
+(START)
+.wrd 1, 2
+.fil 3, 0
+.bogus
+(.loop)
+   @.loop
+   0;JMP
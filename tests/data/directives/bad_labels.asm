@@ -0,0 +1,9 @@
+(.orphan)
+   @.orphan
+(MAIN)
+   @.missing
+   0;JMP
+   @2b
+   0;JMP
+   @3f
+   0;JMP
@@ -0,0 +1,10 @@
+   M=5
+   M[counter]=A+1
+   if M>0 goto END
+   if D>1 goto END
+   if D>0 END
+   goto
+   M[counter]=
+   @5
+   D=40000
+(END)
@@ -0,0 +1,4 @@
+.var pinned 17
+.var SP 300
+.var pinned 18
+.var broken
@@ -0,0 +1,5 @@
+   .fill 32767, 0
+   D=A
+(END)
+   @END
+   0;JMP
@@ -0,0 +1,5 @@
+.var pinned 17
+   @first
+   M=0
+   @second
+   M=0
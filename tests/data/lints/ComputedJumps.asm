@@ -0,0 +1,13 @@
+// Jump targets without labels, like translated code with labels stripped
+
+   @6
+   D=A
+   @R13
+   M=D
+   @8
+   0;JMP
+   @6
+   0;JMP
+   @R13
+   A=M
+   0;JMP
@@ -0,0 +1,18 @@
+// A constant loaded as a value only makes code reachable when it points right after a jump,
+// where a return address lands
+
+   @4
+   D=A
+   @END
+   0;JMP
+   @R0
+   M=D
+   @END
+   0;JMP
+   @R1
+   M=D
+   @9
+   D=A
+(END)
+   @END
+   0;JMP
@@ -0,0 +1,20 @@
+// Unreachable code, an unused label and a missing terminator
+
+(START)
+   @i
+   M=0
+(LOOP)
+   @i
+   M=M+1
+   @LOOP
+   0;JMP
+   @i
+   M=0
+(TABLE)
+   .word 1, 2, 3
+   @R0
+   D=M
+   @TABLE
+   D=A
+   @R1
+   M=D
@@ -0,0 +1,13 @@
+// Jumps in the same instruction as an A or M access
+
+   @R0
+   A=M;JMP
+   @R1
+   AM=M-1;JNE
+   @R2
+   M=D;JGT
+   @LOOP
+   D;JEQ
+(LOOP)
+   @LOOP
+   0;JMP
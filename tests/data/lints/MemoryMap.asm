@@ -0,0 +1,19 @@
+// Constant addresses outside the memory map
+
+   @30000
+   D=M
+   @KBD
+   M=0
+   @16384
+   D=A
+   @13
+   M=D
+   @R13
+   M=D
+   @i
+   M=0
+   @j
+   M=0
+(END)
+   @END
+   0;JMP
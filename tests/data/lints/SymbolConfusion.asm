@@ -0,0 +1,19 @@
+// Labels used as variables and variables used as jump targets
+
+   @count
+   M=0
+(COUNT)
+   @COUNT
+   M=M+1
+(WAIT)
+   @KBD
+   D=M
+   @wait
+   D;JEQ
+   @END
+   D=M
+   @done
+   0;JMP
+(END)
+   @END
+   0;JMP
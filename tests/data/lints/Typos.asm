@@ -0,0 +1,9 @@
+// Misspelled symbols that silently become variables
+
+(LOOP)
+   @counter
+   M=M+1
+   @screen
+   M=-1
+   @LOPP
+   0;JMP
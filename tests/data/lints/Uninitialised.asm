@@ -0,0 +1,24 @@
+// Variables read before they are written on some path
+
+   @total
+   M=0
+   @R0
+   D=M
+   @SKIP
+   D;JEQ
+   @sum
+   M=0
+(SKIP)
+   @sum
+   D=M
+   @count
+   M=M+1
+(LOOP)
+   @total
+   M=M+1
+   D=M
+   @LOOP
+   D;JLT
+(END)
+   @END
+   0;JMP
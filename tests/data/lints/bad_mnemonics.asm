@@ -0,0 +1,4 @@
+   DM=M+1
+   0;JNZ
+   0;jmp
+   Q=D
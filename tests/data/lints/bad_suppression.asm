@@ -0,0 +1,4 @@
+// hackasm: allow(unused-labels)
+(END)
+   @END
+   0;JMP
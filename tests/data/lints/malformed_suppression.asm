@@ -0,0 +1,6 @@
+// hackasm: tiny demo
+(END)
+   @END
+   0;JMP   // hackasm: deny(warnings)
+   0;JMP   // hackasm: allow(jump-writes-a
+   0;JMP   // hackasm: allow()
@@ -0,0 +1,8 @@
+// Two symbols that are the same identifier in C and Rust
+
+(MAIN.loop)
+   @MAIN.loop
+   0;JMP
+(MAIN_LOOP)
+   @MAIN_LOOP
+   0;JMP
@@ -0,0 +1 @@
+// An empty program still needs a valid memory file
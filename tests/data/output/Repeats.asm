@@ -0,0 +1,8 @@
+// Repeated words for run-length encoded formats
+
+   .fill 5, 0
+   .word 1, 1, 2
+   .space 3
+(END)
+   @END
+   0;JMP
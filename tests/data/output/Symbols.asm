@@ -0,0 +1,10 @@
+// Labels, variables and constants exported by the source array formats
+
+(MAIN)
+   @counter
+   M=0
+(.loop)
+   @LIMIT
+   D=A
+   @MAIN.loop
+   D;JGT
@@ -0,0 +1,357 @@
+use assert_cmd::assert;
+use predicates::prelude::*;
+use rusty_hack_asm::Result;
+use std::fs;
+
+fn run_binary(args: &[&str]) -> assert::Assert {
+    assert_cmd::Command::cargo_bin("rusty_hack_asm")
+        .unwrap()
+        .args(args)
+        .assert()
+}
+
+fn output_lines(file: &str) -> Result<Vec<String>> {
+    Ok(fs::read_to_string(file)?
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+#[test]
+fn test_conditional_assembly() -> Result<()> {
+    let source = "tests/data/directives/Conditional.asm";
+    let output = "tests/data/directives/Conditional.hack";
+
+    run_binary(&[source]).success();
+    assert_eq!(
+        output_lines(output)?,
+        [
+            "0100000000000000",
+            "1110110000010000",
+            "0000000000000010",
+            "1110101010000111",
+        ]
+    );
+
+    run_binary(&["-D", "FPGA=0x4000", "-DDEBUG", source]).success();
+    assert_eq!(
+        output_lines(output)?,
+        [
+            "0100000000000000",
+            "1110110000010000",
+            "0000000000000010",
+            "1110101010000111",
+        ]
+    );
+
+    run_binary(&["-D", "DEBUG", source]).success();
+    assert_eq!(
+        output_lines(output)?,
+        [
+            "0100000000000000",
+            "1110110000010000",
+            "0000000000001111",
+            "1110001100001000",
+            "0000000000000100",
+            "1110101010000111",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unbalanced_conditionals() {
+    run_binary(&["tests/data/directives/bad_conditional.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Duplicate '.else' in conditional block",
+        ))
+        .stderr(predicate::str::contains("'.endif' without matching '.if'"))
+        .stderr(predicate::str::contains(
+            "Expected symbol name after '.ifdef'",
+        ))
+        .stderr(predicate::str::contains("Expected ')' after expression"))
+        .stderr(predicate::str::contains(
+            "Unterminated '.ifdef', expected '.endif'",
+        ))
+        .stderr(predicate::str::contains(
+            "Unterminated '.if', expected '.endif'",
+        ));
+}
+
+#[test]
+fn test_local_and_numeric_labels() -> Result<()> {
+    run_binary(&["tests/data/directives/Labels.asm"]).success();
+    assert_eq!(
+        output_lines("tests/data/directives/Labels.hack")?,
+        [
+            "0000000000000000",
+            "1110001100000001",
+            "0000000000000010",
+            "1110101010000111",
+            "0000000000001000",
+            "1110001100000010",
+            "0000000000000000",
+            "1110101010000111",
+            "0000000000000100",
+            "1110101010000111",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bad_local_and_numeric_labels() {
+    run_binary(&["tests/data/directives/bad_labels.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Local label .orphan has no enclosing global label",
+        ))
+        .stderr(predicate::str::contains(
+            "Local label MAIN.missing is never declared",
+        ))
+        .stderr(predicate::str::contains(
+            "No numeric label 2: declared before this reference",
+        ))
+        .stderr(predicate::str::contains(
+            "No numeric label 3: declared after this reference",
+        ));
+}
+
+#[test]
+fn test_pseudo_instructions() -> Result<()> {
+    run_binary(&["tests/data/directives/Pseudo.asm"]).success();
+    assert_eq!(
+        output_lines("tests/data/directives/Pseudo.hack")?,
+        [
+            "0000000000000101",
+            "1110110000010000",
+            "0000000000010000",
+            "1110001100001000",
+            "0000000000010000",
+            "1111110010001000",
+            "0000000000010000",
+            "1111110000010000",
+            "0000000000000100",
+            "1110001100000001",
+            "1110011111010000",
+            "0000000000000100",
+            "1110101010000111",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bad_pseudo_instructions() {
+    run_binary(&["tests/data/directives/bad_pseudo.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot load a constant into M, '@' would overwrite the address in A",
+        ))
+        .stderr(predicate::str::contains(
+            "Computation cannot use A, it holds the address of M[...]",
+        ))
+        .stderr(predicate::str::contains(
+            "Conditional jumps can only compare D with 0",
+        ))
+        .stderr(predicate::str::contains("Expected 'goto' after condition"))
+        // a missing value is reported on its own line, leaving the next statement alone
+        .stderr(predicate::str::contains(
+            "7 |    M[counter]=\n                  ^--here\n error: Expected value after '='",
+        ))
+        .stderr(predicate::str::contains("8 |").not())
+        // constants are loaded through '@', so they are limited like addresses
+        .stderr(predicate::str::contains(
+            "9 |    D=40000\n         ^^^^^--here\n error: Address out of range",
+        ));
+}
+
+#[test]
+fn test_strict_mode_rejects_pseudo_instructions() {
+    run_binary(&["--strict", "tests/data/directives/Pseudo.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Pseudo-instructions are not allowed in strict mode",
+        ));
+}
+
+#[test]
+fn test_data_directives() -> Result<()> {
+    run_binary(&["tests/data/directives/Data.asm"]).success();
+    assert_eq!(
+        output_lines("tests/data/directives/Data.hack")?,
+        [
+            "0000000000000100",
+            "1110110000010000",
+            "0000000000001011",
+            "1110101010000111",
+            "1111111111111111",
+            "1001110001000000",
+            "0000000000000100",
+            "1111111111111110",
+            "0000000000001011",
+            "0000000000001011",
+            "0000000000000000",
+            "0000000010101010",
+            "0000000000010000",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bad_data_directives() {
+    run_binary(&["tests/data/directives/bad_data.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Address out of range. Address ranges from 0 to 32767",
+        ))
+        .stderr(predicate::str::contains(
+            "Expected number or label as data word",
+        ))
+        .stderr(predicate::str::contains(
+            "'.fill' needs a word count of at least 1",
+        ))
+        .stderr(predicate::str::contains(
+            "Expected word count after '.space'",
+        ));
+}
+
+#[test]
+fn test_variable_placement() -> Result<()> {
+    let source = "tests/data/directives/Vars.asm";
+    let output = "tests/data/directives/Vars.hack";
+
+    run_binary(&[source]).success();
+    let lines = output_lines(output)?;
+    assert_eq!(
+        [&lines[0], &lines[2], &lines[4]],
+        ["0000000000010000", "0000000100000000", "0000000000010001"]
+    );
+
+    run_binary(&["--var-base=1024", source]).success();
+    let lines = output_lines(output)?;
+    assert_eq!(
+        [&lines[0], &lines[2], &lines[4]],
+        ["0000010000000000", "0000000100000000", "0000010000000001"]
+    );
+
+    run_binary(&["--var-base", "255", "--var-limit", "256", source])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Variable total is allocated to address 256, which is already used by '.var stack'",
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_bad_variable_placement() {
+    run_binary(&["tests/data/directives/bad_vars.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Identifier SP is predefined and cannot be redefined",
+        ))
+        .stderr(predicate::str::contains(
+            "Cannot place variable pinned, it is already defined",
+        ))
+        .stderr(predicate::str::contains(
+            "Expected variable address from 0 to 32767 after variable name",
+        ));
+    run_binary(&["tests/data/directives/var_collision.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Variable second is allocated to address 17, which is already used by '.var pinned'",
+        ));
+}
+
+#[cfg(feature = "extended-isa")]
+#[test]
+fn test_shift_instructions() -> Result<()> {
+    run_binary(&["--extended-isa", "tests/data/directives/Shift.asm"]).success();
+    assert_eq!(
+        output_lines("tests/data/directives/Shift.hack")?,
+        [
+            "1010001100010000",
+            "1010001100010000",
+            "0000000000000000",
+            "1001110000001000",
+            "1010110000101111",
+        ]
+    );
+    run_binary(&["tests/data/directives/Shift.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Shift instructions require the --extended-isa flag",
+        ));
+    Ok(())
+}
+
+#[cfg(not(feature = "extended-isa"))]
+#[test]
+fn test_shift_instructions_need_feature() {
+    run_binary(&["--extended-isa", "tests/data/directives/Shift.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Shift instructions require building with the 'extended-isa' cargo feature",
+        ));
+}
+
+#[test]
+fn test_assertions() {
+    run_binary(&["tests/data/directives/Assert.asm"]).success();
+    run_binary(&["tests/data/directives/bad_assert.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            " error: Assertion failed: END > 100\n note: 2 > 100 is false, where END = 2",
+        ))
+        .stderr(predicate::str::contains(
+            " error: Assertion failed: buffer overlaps the screen\n \
+             note: 20000 <= 16384 is false, where BUF_END = 20000, SCREEN = 16384",
+        ))
+        .stderr(predicate::str::contains(
+            "note: 16 == 17 is false, where counter = 16",
+        ))
+        .stderr(predicate::str::contains(
+            "Unknown symbol 'MISSING' in expression",
+        ));
+}
+
+#[test]
+fn test_rom_overflow() {
+    run_binary(&["tests/data/directives/rom_overflow.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Label END points at address 32768, past the end of ROM",
+        ))
+        .stderr(predicate::str::contains(
+            "4 |    @END\n       ^^^^--here\n error: Program does not fit in ROM, \
+             it is 32770 words long but ROM holds 32768 words",
+        ));
+}
+
+#[test]
+fn test_define_out_of_address_range() {
+    run_binary(&["-D", "BIG=40000", "tests/data/directives/bad_define.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "3 |    @BIG\n        ^^^--here\n error: Constant BIG is 40000, \
+             which is out of range as an address. Address ranges from 0 to 32767",
+        ))
+        .stderr(predicate::str::contains("Encountered 1, errors"));
+}
+
+#[test]
+fn test_unknown_directive() {
+    run_binary(&["tests/data/directives/bad_directive.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "4 | .wrd 1, 2\n    ^^^^--here\n error: Unknown directive '.wrd', did you mean '.word'?",
+        ))
+        .stderr(predicate::str::contains(
+            "Unknown directive '.fil', did you mean '.fill'?",
+        ))
+        .stderr(predicate::str::contains("Unknown directive '.bogus'\n"))
+        .stderr(predicate::str::contains("Encountered 3 errors"))
+        .stderr(predicate::str::contains("computation").not());
+}
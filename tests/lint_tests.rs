@@ -0,0 +1,276 @@
+use assert_cmd::assert;
+use predicates::prelude::*;
+
+fn run_binary(args: &[&str]) -> assert::Assert {
+    assert_cmd::Command::cargo_bin("rusty_hack_asm")
+        .unwrap()
+        .args(args)
+        .assert()
+}
+
+#[test]
+fn test_misspelled_symbols() {
+    run_binary(&["tests/data/lints/Typos.asm"])
+        .success()
+        .stderr(predicate::str::contains(
+            " warning: Variable screen is not the predefined symbol SCREEN, \
+             symbols are case-sensitive [similar-name]\n help: did you mean `SCREEN`?",
+        ))
+        .stderr(predicate::str::contains(
+            " warning: Variable LOPP is only used once [similar-name]\n \
+             help: did you mean `LOOP`?",
+        ))
+        .stderr(predicate::str::contains("Variable counter is only used once").not());
+}
+
+#[test]
+fn test_misspelled_mnemonics() {
+    run_binary(&["tests/data/lints/bad_mnemonics.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            " error: Expected destination after '='\n help: did you mean `MD`?",
+        ))
+        .stderr(predicate::str::contains(
+            " error: Expected jump keyword after ';'\n help: did you mean `JNE`?",
+        ))
+        .stderr(predicate::str::contains(
+            " error: Expected jump keyword after ';'\n help: did you mean `JMP`?",
+        ));
+}
+
+#[test]
+fn test_jump_hazards() {
+    run_binary(&["tests/data/lints/JumpHazards.asm"])
+        .success()
+        .stderr(predicate::str::contains(
+            "4 |    A=M;JMP\n       ^^^^^^^--here\n warning: Instruction writes A and jumps, \
+             the jump target is the old value of A [jump-writes-a]",
+        ))
+        .stderr(predicate::str::contains("6 |    AM=M-1;JNE"))
+        .stderr(predicate::str::contains(
+            "8 |    M=D;JGT\n       ^^^^^^^--here\n warning: Instruction uses M and jumps, \
+             A is both the memory address and the jump target [jump-accesses-memory]",
+        ))
+        .stderr(predicate::str::contains("Emitted 3 warnings"));
+}
+
+#[test]
+fn test_denied_lints() {
+    run_binary(&[
+        "--deny",
+        "jump-writes-a",
+        "tests/data/lints/JumpHazards.asm",
+    ])
+    .failure()
+    .stderr(predicate::str::contains(
+        " error: Instruction writes A and jumps, the jump target is the old value of A \
+             [jump-writes-a]\n help: load the jump target into A in a separate instruction\n \
+             note: `jump-writes-a` is denied",
+    ))
+    .stderr(predicate::str::contains("Emitted 1 warnings"))
+    .stderr(predicate::str::contains("Encountered 2 errors"));
+
+    run_binary(&[
+        "--deny=warnings",
+        "--allow=jump-writes-a",
+        "tests/data/lints/JumpHazards.asm",
+    ])
+    .failure()
+    .stderr(predicate::str::contains("[jump-writes-a]").not())
+    .stderr(predicate::str::contains("Encountered 1 errors"));
+
+    run_binary(&[
+        "--allow",
+        "warnings",
+        "--warn",
+        "jump-accesses-memory",
+        "tests/data/lints/JumpHazards.asm",
+    ])
+    .success()
+    .stderr(predicate::str::contains("Emitted 1 warnings"));
+}
+
+#[test]
+fn test_unknown_lint() {
+    run_binary(&["--deny", "jump-write-a", "tests/data/lints/JumpHazards.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "Unknown lint 'jump-write-a', did you mean 'jump-writes-a'?",
+        ));
+}
+
+#[test]
+fn test_control_flow() {
+    run_binary(&["tests/data/lints/ControlFlow.asm"])
+        .success()
+        .stderr(predicate::str::contains(
+            "3 | (START)\n    ^^^^^^^--here\n warning: Label START is never referenced [unused-label]",
+        ))
+        .stderr(predicate::str::contains("Label LOOP").not())
+        .stderr(predicate::str::contains("Label TABLE").not())
+        .stderr(predicate::str::contains(
+            "11 |    @i\n        ^^--here\n warning: 2 unreachable instructions after an \
+             unconditional jump [unreachable-code]",
+        ))
+        .stderr(predicate::str::contains(
+            "20 |    M=D\n        ^^^--here\n warning: Execution continues past the last \
+             instruction [fall-off-end]",
+        ))
+        .stderr(predicate::str::contains("Emitted 3 warnings"));
+}
+
+#[test]
+fn test_constant_jump_targets() {
+    // addresses like `@8 0;JMP` are jump targets even without a label
+    run_binary(&["tests/data/lints/ComputedJumps.asm"])
+        .success()
+        .stderr(predicate::str::contains("warning").not());
+}
+
+#[test]
+fn test_constant_loads_as_jump_targets() {
+    // `@4 D=A` is a return address right after a jump, `@9 D=A` points into the middle of code
+    run_binary(&["tests/data/lints/ConstantLoads.asm"])
+        .success()
+        .stderr(predicate::str::contains("@R0").not())
+        .stderr(predicate::str::contains(
+            "12 |    @R1\n        ^^^--here\n warning: 4 unreachable instructions after an \
+             unconditional jump [unreachable-code]",
+        ))
+        .stderr(predicate::str::contains("Emitted 1 warnings"));
+}
+
+#[test]
+fn test_label_less_program_reachability() {
+    // compiled VM code without labels only enters functions through computed jumps
+    run_binary(&["tests/data/asm/PongL.asm"])
+        .success()
+        .stderr(predicate::str::contains("unreachable-code").not());
+}
+
+#[test]
+fn test_label_variable_confusion() {
+    run_binary(&["tests/data/lints/SymbolConfusion.asm"])
+        .success()
+        .stderr(predicate::str::contains(
+            " warning: Label COUNT is a ROM address, but M accesses RAM at that address \
+             [label-as-variable]\n help: did you mean `count`?",
+        ))
+        .stderr(predicate::str::contains(
+            " warning: Variable wait is a RAM address, but it is used as a jump target \
+             [variable-as-label]\n help: did you mean `WAIT`?",
+        ))
+        .stderr(predicate::str::contains(
+            " warning: Label END is a ROM address, but M accesses RAM at that address \
+             [label-as-variable]\n help: use A for the label's address, M reads and writes RAM",
+        ))
+        .stderr(predicate::str::contains(
+            " warning: Variable done is a RAM address, but it is used as a jump target \
+             [variable-as-label]\n help: declare a label `(done)` to jump to it",
+        ));
+}
+
+#[test]
+fn test_memory_map() {
+    run_binary(&[
+        "--var-base=0x4000",
+        "--var-limit=0x5000",
+        "tests/data/lints/MemoryMap.asm",
+    ])
+    .success()
+    .stderr(predicate::str::contains(
+        "3 |    @30000\n       ^^^^^^--here\n warning: Address 30000 is past the end of RAM, \
+         the last register is KBD at 24576 [unmapped-memory]",
+    ))
+    .stderr(predicate::str::contains(
+        "5 |    @KBD\n       ^^^^--here\n warning: Writing to KBD has no effect, \
+         the keyboard register is read-only [keyboard-write]",
+    ))
+    .stderr(predicate::str::contains(
+        " warning: Address 16384 is the predefined symbol SCREEN [magic-address]\n \
+         help: use `@SCREEN` to make the address clearer",
+    ))
+    .stderr(predicate::str::contains(
+        " warning: Address 13 is the predefined symbol R13 [magic-address]",
+    ))
+    .stderr(predicate::str::contains(
+        " warning: Variable i is allocated to address 16384 in screen memory [screen-variable]",
+    ))
+    .stderr(predicate::str::contains("Emitted 6 warnings"));
+}
+
+#[test]
+fn test_uninitialised_reads() {
+    run_binary(&["tests/data/lints/Uninitialised.asm"])
+        .success()
+        .stderr(predicate::str::contains(
+            "13 |    D=M\n        ^^^--here\n warning: Variable sum may be read before it is \
+             written [uninitialised-read]",
+        ))
+        .stderr(predicate::str::contains(
+            "15 |    M=M+1\n        ^^^^^--here\n warning: Variable count may be read before \
+             it is written [uninitialised-read]",
+        ))
+        .stderr(predicate::str::contains("Variable total").not())
+        .stderr(predicate::str::contains("Emitted 2 warnings"));
+}
+
+#[test]
+fn test_project_config() {
+    run_binary(&["tests/data/config/Project.asm"])
+        .success()
+        .stderr(predicate::str::contains(
+            "Label UNUSED is never referenced [unused-label]",
+        ))
+        .stderr(predicate::str::contains("Label START").not())
+        .stderr(predicate::str::contains("[magic-address]").not())
+        .stderr(predicate::str::contains("Emitted 1 warnings"));
+    // `var-base = 0x20` places the first variable at 32
+    let output = std::fs::read_to_string("tests/data/config/Project.hack").unwrap();
+    assert_eq!(output.lines().next(), Some("0000000000100000"));
+
+    run_binary(&["tests/data/config/Uninitialised.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            " error: Variable total may be read before it is written [uninitialised-read]",
+        ));
+    run_binary(&[
+        "--warn",
+        "uninitialised-read",
+        "tests/data/config/Uninitialised.asm",
+    ])
+    .success()
+    .stderr(predicate::str::contains(
+        " warning: Variable total may be read before it is written [uninitialised-read]",
+    ));
+}
+
+#[test]
+fn test_invalid_config() {
+    run_binary(&["tests/data/config_bad/Empty.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "hackasm.toml:4: Unknown lint 'unused-labels', did you mean 'unused-label'?",
+        ));
+}
+
+#[test]
+fn test_invalid_suppressions() {
+    run_binary(&["tests/data/lints/bad_suppression.asm"])
+        .failure()
+        .stderr(predicate::str::contains(
+            " error: Unknown lint 'unused-labels' in suppression\n \
+             help: did you mean `unused-label`?",
+        ));
+}
+
+#[test]
+fn test_non_suppression_comments() {
+    // only `hackasm: allow(...)` is a suppression, other comments never fail compilation
+    run_binary(&["tests/data/lints/malformed_suppression.asm"])
+        .success()
+        .stderr(predicate::str::contains(
+            "5 |    0;JMP   // hackasm: allow(jump-writes-a\n       ^^^^^--here\n \
+             warning: 2 unreachable instructions after an unconditional jump [unreachable-code]",
+        ));
+}
@@ -0,0 +1,463 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rusty_hack_asm::Endianness;
+
+// each test compiles its own copy of the source, so outputs of parallel tests don't collide
+fn compile_in_temp_dir(source: &str, test: &str, args: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join("rusty_hack_asm_tests").join(test);
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join(Path::new(source).file_name().unwrap());
+    fs::copy(source, &file).unwrap();
+    assert_cmd::Command::cargo_bin("rusty_hack_asm")
+        .unwrap()
+        .args(args)
+        .arg(&file)
+        .assert()
+        .success();
+    file
+}
+
+fn expected_words(name: &str) -> Vec<u16> {
+    fs::read_to_string(format!("tests/data/expected/{}.hack", name))
+        .unwrap()
+        .lines()
+        .map(|line| u16::from_str_radix(line, 2).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_binary_big_endian() {
+    let file = compile_in_temp_dir("tests/data/asm/Max.asm", "bin_big", &["--format=bin"]);
+    let bytes = fs::read(file.with_extension("bin")).unwrap();
+    let expected: Vec<u8> = expected_words("Max")
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .collect();
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn test_binary_little_endian() {
+    let file = compile_in_temp_dir(
+        "tests/data/asm/Pong.asm",
+        "bin_little",
+        &["--format", "bin", "--endian", "little"],
+    );
+    let bytes = fs::read(file.with_extension("bin")).unwrap();
+    let expected: Vec<u8> = expected_words("Pong")
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    assert_eq!(bytes, expected);
+    assert!(!file.with_extension("hack").exists());
+}
+
+#[test]
+fn test_trailing_newline() {
+    let file = compile_in_temp_dir(
+        "tests/data/asm/Add.asm",
+        "trailing_newline",
+        &["--line-ending=lf", "--no-trailing-newline"],
+    );
+    let output = fs::read_to_string(file.with_extension("hack")).unwrap();
+    let expected = fs::read_to_string("tests/data/expected/Add.hack").unwrap();
+    assert_eq!(output, expected.trim_end());
+}
+
+#[test]
+fn test_native_line_ending() {
+    let file = compile_in_temp_dir(
+        "tests/data/asm/Add.asm",
+        "native_line_ending",
+        &["--line-ending", "native"],
+    );
+    let output = fs::read_to_string(file.with_extension("hack")).unwrap();
+    let line_ending = if cfg!(windows) { "\r\n" } else { "\n" };
+    let expected = fs::read_to_string("tests/data/expected/Add.hack").unwrap();
+    assert_eq!(output, expected.replace('\n', line_ending));
+}
+
+#[test]
+fn test_intel_hex() {
+    let file = compile_in_temp_dir("tests/data/asm/Max.asm", "ihex", &["--format=ihex"]);
+    let output = fs::read_to_string(file.with_extension("hex")).unwrap();
+    assert_eq!(
+        output,
+        ":100000000000FC100001F4D0000AE3010001FC1024\r\n\
+         :10001000000CEA870000FC100002E308000EEA87EB\r\n\
+         :00000001FF\r\n"
+    );
+}
+
+#[test]
+fn test_intel_hex_round_trip() {
+    let file = compile_in_temp_dir(
+        "tests/data/asm/Pong.asm",
+        "ihex_round_trip",
+        &["--format", "ihex", "--endian", "little"],
+    );
+    let output = fs::read_to_string(file.with_extension("hex")).unwrap();
+    let words = rusty_hack_asm::read_intel_hex(&output, Endianness::Little).unwrap();
+    assert_eq!(words, expected_words("Pong"));
+}
+
+#[test]
+fn test_read_intel_hex() {
+    let words =
+        rusty_hack_asm::read_intel_hex(":0400000012345678E8\n:00000001FF\n", Endianness::Big)
+            .unwrap();
+    assert_eq!(words, [0x1234, 0x5678]);
+
+    // extended segment address records move the following data
+    let words = rusty_hack_asm::read_intel_hex(
+        ":020000020800F4\n:0400000012345678E8\n:00000001FF\n",
+        Endianness::Big,
+    )
+    .unwrap();
+    assert_eq!(words.len(), 0x4002);
+    assert_eq!(words[0x4000..], [0x1234, 0x5678]);
+
+    // data past 64K doesn't fit in ROM, however far the base address points
+    for text in [
+        ":020000040001F9\n:0400000012345678E8\n",
+        ":02000004FFFFFC\n:0400000012345678E8\n",
+    ] {
+        let error = rusty_hack_asm::read_intel_hex(text, Endianness::Big)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Intel HEX line 2: Data past the end of ROM, which holds 32768 words"
+        );
+    }
+
+    let error = rusty_hack_asm::read_intel_hex(":00000006FA\n", Endianness::Big)
+        .unwrap_err()
+        .to_string();
+    assert_eq!(error, "Intel HEX line 1: Unknown record type 06");
+
+    let error = rusty_hack_asm::read_intel_hex(":0400000012345678E9\n", Endianness::Big)
+        .unwrap_err()
+        .to_string();
+    assert_eq!(error, "Intel HEX line 1: Checksum mismatch");
+
+    // non-ASCII and sign characters are rejected rather than sliced or parsed
+    for text in [":0000000é1FF\n", ":+0000001FF\n"] {
+        let error = rusty_hack_asm::read_intel_hex(text, Endianness::Big)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "Intel HEX line 1: Invalid hex digit");
+    }
+}
+
+#[test]
+fn test_logisim() {
+    let file = compile_in_temp_dir(
+        "tests/data/asm/Max.asm",
+        "logisim",
+        &["--format=logisim", "--line-ending=lf"],
+    );
+    let output = fs::read_to_string(file.with_extension("rom")).unwrap();
+    assert_eq!(
+        output,
+        "v2.0 raw\n0 fc10 1 f4d0 a e301 1 fc10\nc ea87 0 fc10 2 e308 e ea87\n"
+    );
+
+    let file = compile_in_temp_dir(
+        "tests/data/output/Repeats.asm",
+        "logisim_repeats",
+        &["--format=logisim", "--line-ending=lf"],
+    );
+    let output = fs::read_to_string(file.with_extension("rom")).unwrap();
+    assert_eq!(output, "v2.0 raw\n5*0 1 1 2 3*0 b ea87\n");
+}
+
+#[test]
+fn test_readmem() {
+    let file = compile_in_temp_dir(
+        "tests/data/output/Repeats.asm",
+        "readmemb",
+        &["--format=readmemb", "--line-ending=lf", "--depth=14"],
+    );
+    let output = fs::read_to_string(file.with_extension("mem")).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 14);
+    assert_eq!(lines[5], "0000000000000001");
+    assert_eq!(lines[11], "0000000000001011");
+    assert_eq!(lines[13], "0000000000000000");
+
+    let file = compile_in_temp_dir(
+        "tests/data/output/Repeats.asm",
+        "readmemh",
+        &[
+            "--format=readmemh",
+            "--line-ending=lf",
+            "--mem-addresses",
+            "--mem-comments",
+        ],
+    );
+    let output = fs::read_to_string(file.with_extension("mem")).unwrap();
+    assert_eq!(
+        output,
+        "@0000\n0000 // .fill 5, 0\n0000 // .fill 5, 0\n0000 // .fill 5, 0\n\
+         0000 // .fill 5, 0\n0000 // .fill 5, 0\n0001 // .word 1, 1, 2\n\
+         0001 // .word 1, 1, 2\n0002 // .word 1, 1, 2\n0000 // .space 3\n\
+         0000 // .space 3\n0000 // .space 3\n@000b\n000b // @END\nea87 // 0;JMP\n"
+    );
+}
+
+#[test]
+fn test_depth_too_small() {
+    assert_cmd::Command::cargo_bin("rusty_hack_asm")
+        .unwrap()
+        .args([
+            "--format=readmemh",
+            "--depth=4",
+            "tests/data/output/Repeats.asm",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Depth 4 is smaller than the program, which is 13 words long",
+        ));
+}
+
+#[test]
+fn test_mif() {
+    let file = compile_in_temp_dir(
+        "tests/data/output/Repeats.asm",
+        "mif",
+        &[
+            "--format=mif",
+            "--line-ending=lf",
+            "--radix=bin",
+            "--depth=32768",
+        ],
+    );
+    let output = fs::read_to_string(file.with_extension("mif")).unwrap();
+    assert!(output.starts_with(
+        "WIDTH=16;\nDEPTH=32768;\n\nADDRESS_RADIX=HEX;\nDATA_RADIX=BIN;\n\nCONTENT BEGIN\n\
+         \t0000 : 0000000000000000;\n"
+    ));
+    assert!(output.ends_with("\t000C : 1110101010000111;\n\t[000D..7FFF] : 0;\nEND;\n"));
+}
+
+#[test]
+fn test_coe() {
+    let file = compile_in_temp_dir(
+        "tests/data/output/Repeats.asm",
+        "coe",
+        &[
+            "--format=coe",
+            "--line-ending=lf",
+            "--radix=dec",
+            "--depth=14",
+        ],
+    );
+    let output = fs::read_to_string(file.with_extension("coe")).unwrap();
+    assert_eq!(
+        output,
+        "memory_initialization_radix=10;\nmemory_initialization_vector=\n\
+         0,\n0,\n0,\n0,\n0,\n1,\n1,\n2,\n0,\n0,\n0,\n11,\n60039,\n0;\n"
+    );
+}
+
+#[test]
+fn test_source_arrays() {
+    let args = ["--line-ending=lf", "-D", "LIMIT=3"];
+    let file = compile_in_temp_dir(
+        "tests/data/output/Symbols.asm",
+        "c",
+        &[&args[..], &["--format=c"]].concat(),
+    );
+    let output = fs::read_to_string(file.with_extension("c")).unwrap();
+    assert_eq!(
+        output,
+        "// Assembled from Symbols.asm by rusty_hack_asm\n#include <stdint.h>\n\n\
+         // labels\n#define SYMBOLS_MAIN 0x0000\n#define SYMBOLS_MAIN_LOOP 0x0002\n\n\
+         // variables\n#define SYMBOLS_COUNTER 0x0010\n\n\
+         // constants\n#define SYMBOLS_LIMIT 0x0003\n\n\
+         const uint16_t SYMBOLS[] = {\n    \
+         0x0010, 0xea88, 0x0003, 0xec10, 0x0002, 0xe301,\n};\n"
+    );
+
+    let file = compile_in_temp_dir(
+        "tests/data/output/Symbols.asm",
+        "rust",
+        &[&args[..], &["--format=rust"]].concat(),
+    );
+    let output = fs::read_to_string(file.with_extension("rs")).unwrap();
+    assert!(output.contains("pub const SYMBOLS_MAIN_LOOP: u16 = 0x0002;\n"));
+    assert!(output.ends_with(
+        "pub const SYMBOLS: [u16; 6] = [\n    \
+         0x0010, 0xea88, 0x0003, 0xec10, 0x0002, 0xe301,\n];\n"
+    ));
+}
+
+#[test]
+fn test_source_array_symbol_collision() {
+    assert_cmd::Command::cargo_bin("rusty_hack_asm")
+        .unwrap()
+        .args(["--format=c", "tests/data/output/Collision.asm"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "Symbols 'MAIN.loop' and 'MAIN_LOOP' both become 'COLLISION_MAIN_LOOP'",
+        ));
+}
+
+#[test]
+fn test_listing() {
+    let file = compile_in_temp_dir(
+        "tests/data/directives/Pseudo.asm",
+        "listing",
+        &["--listing", "--line-ending=lf"],
+    );
+    // the listing comes in addition to the normal output
+    assert!(file.with_extension("hack").exists());
+    let listing = fs::read_to_string(file.with_extension("lst")).unwrap();
+    let lines: Vec<&str> = listing.lines().collect();
+    assert_eq!(lines[0], " line  addr  binary            hex   source");
+    assert_eq!(
+        lines[1],
+        "    1                                // Counts down from 5 using pseudo-instructions"
+    );
+    assert_eq!(lines[2], "    2");
+    // pseudo-instructions are followed by the instructions they expand to
+    assert_eq!(
+        lines[3..6],
+        [
+            "    3  0000                             D=5",
+            "       0000  0000000000000101  0005       @5",
+            "       0001  1110110000010000  ec10       D=A",
+        ]
+    );
+    assert_eq!(lines[9], "    5  0004                          (LOOP)");
+    assert_eq!(
+        lines[16..19],
+        [
+            "    8  0008                             if D>0 goto LOOP",
+            "       0008  0000000000000100  0004       @4",
+            "       0009  1110001100000001  e301       D;JGT",
+        ]
+    );
+    assert!(
+        listing.ends_with("\nsymbols\n  labels\n    0004  LOOP\n  variables\n    0010  counter\n")
+    );
+}
+
+#[test]
+fn test_symbol_tables() {
+    let file = compile_in_temp_dir(
+        "tests/data/output/Symbols.asm",
+        "symbols",
+        &[
+            "--symbols",
+            "--symbols-json",
+            "--line-ending=lf",
+            "-D",
+            "LIMIT=3",
+        ],
+    );
+    let text = fs::read_to_string(file.with_extension("sym")).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 1 + 23 + 2 + 1 + 1);
+    assert_eq!(lines[0], "# address kind name");
+    assert_eq!(lines[1], "0     predefined R0");
+    assert_eq!(lines[23], "24576 predefined KBD");
+    assert_eq!(
+        lines[24..],
+        [
+            "0     label      MAIN",
+            "2     label      MAIN.loop",
+            "16    variable   counter",
+            "3     constant   LIMIT",
+        ]
+    );
+
+    let json = fs::read_to_string(file.with_extension("sym.json")).unwrap();
+    assert!(
+        json.starts_with("[\n  {\"name\": \"R0\", \"kind\": \"predefined\", \"address\": 0},\n")
+    );
+    assert!(json.ends_with(
+        "  {\"name\": \"counter\", \"kind\": \"variable\", \"address\": 16},\n  \
+         {\"name\": \"LIMIT\", \"kind\": \"constant\", \"address\": 3}\n]\n"
+    ));
+
+    let file = compile_in_temp_dir(
+        "tests/data/output/Symbols.asm",
+        "symbols_by_name",
+        &["--symbols", "--symbol-order=name", "--line-ending=lf"],
+    );
+    let text = fs::read_to_string(file.with_extension("sym")).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[1], "2     predefined ARG");
+    assert_eq!(lines[2], "24576 predefined KBD");
+}
+
+#[test]
+fn test_source_map() {
+    let file = compile_in_temp_dir(
+        "tests/data/directives/Pseudo.asm",
+        "source_map",
+        &["--source-map", "--line-ending=lf"],
+    );
+    let map = fs::read_to_string(file.with_extension("map.json")).unwrap();
+    let lines: Vec<&str> = map.lines().collect();
+    assert_eq!(lines.len(), 3 + 13 + 2);
+    assert_eq!(
+        lines[..5],
+        [
+            "{",
+            format!(
+                "  \"file\": \"{}\",",
+                file.display().to_string().replace('\\', "\\\\")
+            )
+            .as_str(),
+            "  \"words\": [",
+            "    {\"address\": 0, \"line\": 3, \"column\": 4, \"end_column\": 7, \"index\": 0},",
+            "    {\"address\": 1, \"line\": 3, \"column\": 4, \"end_column\": 7, \"index\": 1},",
+        ]
+    );
+    assert_eq!(
+        lines[15],
+        "    {\"address\": 12, \"line\": 10, \"column\": 4, \"end_column\": 13, \"index\": 1}"
+    );
+    assert_eq!(lines[16..], ["  ]", "}"]);
+}
+
+#[test]
+fn test_empty_program_memory_files() {
+    let file = compile_in_temp_dir(
+        "tests/data/output/Empty.asm",
+        "empty_mif",
+        &["--format=mif", "--line-ending=lf"],
+    );
+    let output = fs::read_to_string(file.with_extension("mif")).unwrap();
+    assert_eq!(
+        output,
+        "WIDTH=16;\nDEPTH=1;\n\nADDRESS_RADIX=HEX;\nDATA_RADIX=HEX;\n\n\
+         CONTENT BEGIN\n\t0000 : 0;\nEND;\n"
+    );
+
+    let file = compile_in_temp_dir(
+        "tests/data/output/Empty.asm",
+        "empty_coe",
+        &["--format=coe", "--line-ending=lf"],
+    );
+    let output = fs::read_to_string(file.with_extension("coe")).unwrap();
+    assert_eq!(
+        output,
+        "memory_initialization_radix=16;\nmemory_initialization_vector=\n0000;\n"
+    );
+
+    assert_cmd::Command::cargo_bin("rusty_hack_asm")
+        .unwrap()
+        .args(["--format=coe", "--depth=0", "tests/data/output/Empty.asm"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Depth must be at least 1 word"));
+}